@@ -0,0 +1,93 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// FNV-1a offset basis for 64 bit hashes, see `src/hash.rs`.
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// FNV-1a prime for 64 bit hashes, see `src/hash.rs`.
+const PRIME: u64 = 0x0100_0000_01b3;
+
+/// Exposes an identifier of the sources antenna is built from as `ANTENNA_BUILD_ID`, and the
+/// versions of the Tree Sitter crates it is built with as `ANTENNA_GRAMMAR_VERSIONS`, allowing
+/// cached results to be invalidated whenever either changes.
+fn main() {
+    let manifest_directory = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+
+    let mut paths = Vec::new();
+    collect_files(&manifest_directory.join("src"), &mut paths);
+    paths.push(manifest_directory.join("Cargo.toml"));
+    paths.sort();
+
+    let mut state = OFFSET_BASIS;
+
+    for path in &paths {
+        let relative = path.strip_prefix(&manifest_directory).unwrap_or(path);
+
+        hash(&mut state, relative.to_string_lossy().as_bytes());
+        hash(&mut state, &fs::read(path).unwrap_or_default());
+    }
+
+    println!("cargo:rustc-env=ANTENNA_BUILD_ID={state:016x}");
+
+    let lockfile =
+        fs::read_to_string(manifest_directory.join("Cargo.lock")).unwrap_or_default();
+    let versions = grammar_versions(&lockfile);
+
+    println!("cargo:rustc-env=ANTENNA_GRAMMAR_VERSIONS={versions}");
+}
+
+/// Collects every file within the given `directory`, recursively.
+fn collect_files(directory: &Path, paths: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        match path.is_dir() {
+            true => collect_files(&path, paths),
+            false => paths.push(path),
+        }
+    }
+}
+
+/// Writes the `bytes` followed by a separator into the FNV-1a `state`.
+fn hash(state: &mut u64, bytes: &[u8]) {
+    for byte in bytes.iter().chain([&0xFF]) {
+        *state ^= u64::from(*byte);
+        *state = state.wrapping_mul(PRIME);
+    }
+}
+
+/// Lists the `name@version` of every Tree Sitter package in the given `lockfile`, separated by
+/// commas.
+fn grammar_versions(lockfile: &str) -> String {
+    let mut versions = Vec::new();
+
+    for package in lockfile.split("[[package]]").skip(1) {
+        let field = |key: &str| {
+            package.lines().find_map(|line| {
+                line.strip_prefix(key)?
+                    .trim_start()
+                    .strip_prefix('=')
+                    .map(|x| x.trim().trim_matches('"'))
+            })
+        };
+
+        if let (Some(name), Some(version)) = (field("name"), field("version")) {
+            if name.starts_with("tree-sitter") {
+                versions.push(format!("{name}@{version}"));
+            }
+        }
+    }
+
+    versions.sort();
+    versions.join(",")
+}
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use antenna::configuration::{AntennaErrorPolicy, AntennaSeverity};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None, help_template = "{name} ({version})\n{about-section}{author-section}\n{usage-heading} {usage}\n\n{all-args}")]
@@ -13,4 +14,175 @@ pub struct AntennaArguments {
         default_value = "./antenna.yml"
     )]
     pub configuration_file: PathBuf,
+
+    /// How errors encountered while indexing files are handled, overrides the `error_policy` of
+    /// the configuration file.
+    #[arg(long, global = true, env = "ANTENNA_ERROR_POLICY")]
+    pub error_policy: Option<AntennaErrorPolicy>,
+
+    /// Maximum number of threads used to index files and execute queries, defaults to the number
+    /// of logical CPUs.
+    #[arg(short, long, global = true, env = "ANTENNA_JOBS")]
+    pub jobs: Option<usize>,
+
+    /// Exits with a non-zero status if any query with at least the given severity has matches.
+    #[arg(long, env = "ANTENNA_FAIL_ON")]
+    pub fail_on: Option<AntennaSeverity>,
+
+    /// Path to a baseline written via `antenna baseline`, only matches that are not part of the
+    /// baseline are reported. Only supported when executing or watching every query.
+    #[arg(long, env = "ANTENNA_BASELINE")]
+    pub baseline: Option<PathBuf>,
+
+    /// Restricts matches to lines changed since the given git revision, including uncommitted
+    /// changes and untracked files. Only supported when executing every query.
+    #[arg(long, value_name = "REVISION", env = "ANTENNA_CHANGED_SINCE")]
+    pub changed_since: Option<String>,
+
+    /// Directory results are cached in between runs, files whose content, query and grammar are
+    /// unchanged are skipped and their cached results are reported instead. Only supported when
+    /// executing every query or writing a baseline.
+    #[arg(long, value_name = "DIRECTORY", env = "ANTENNA_CACHE_DIR")]
+    pub cache_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<AntennaCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AntennaCommand {
+    /// Executes a single query against the included files, printing matches to stdout.
+    Query(QueryArguments),
+
+    /// Writes a baseline of the current matches of every query in the configuration file.
+    Baseline(BaselineArguments),
+
+    /// Executes every query in the configuration file, then re-executes queries whose files
+    /// change until interrupted.
+    Watch,
+
+    /// Applies the `fix` of every query in the configuration file to the source of its matches.
+    Fix(FixArguments),
+
+    /// Executes the `tests` of every query in the configuration file.
+    Test(TestArguments),
+
+    /// Prints the syntax tree of a file, to help with writing queries.
+    Tree(TreeArguments),
+
+    /// Runs a language server over stdin and stdout, publishing matches of the queries in the
+    /// configuration file as diagnostics of open documents.
+    Lsp,
+}
+
+#[derive(Debug, Args)]
+pub struct BaselineArguments {
+    /// Path the baseline is written to.
+    #[arg(short, long, default_value = "./antenna-baseline.json")]
+    pub output: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct FixArguments {
+    /// Prints a unified diff of the fixes instead of applying them.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TestArguments {
+    /// Executes only tests whose name, `query::test`, contains the given text.
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct TreeArguments {
+    /// Path to the file to parse.
+    pub path: PathBuf,
+
+    /// Name of the language the file is parsed as, detected from the file if omitted.
+    #[arg(short, long)]
+    pub language: Option<String>,
+
+    /// Format the syntax tree is printed in.
+    #[arg(short, long, value_enum, default_value_t = TreeFormat::Sexp)]
+    pub format: TreeFormat,
+
+    /// Prints only nodes overlapping the given zero-based rows, either `ROW` or `START:END`
+    /// inclusive.
+    #[arg(short, long, value_parser = parse_row_range)]
+    pub range: Option<(usize, usize)>,
+
+    /// Includes anonymous nodes, such as punctuation and keywords.
+    #[arg(short, long)]
+    pub anonymous: bool,
+}
+
+/// Formats a syntax tree can be printed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TreeFormat {
+    /// An S-expression, in the syntax of Tree Sitter queries.
+    Sexp,
+
+    /// An indented outline with one node per line, including the text of named leaf nodes.
+    Outline,
+}
+
+#[derive(Debug, Args)]
+pub struct QueryArguments {
+    /// Glob patterns of files the query should be executed against.
+    #[arg(short, long, required = true)]
+    pub include: Vec<String>,
+
+    /// Glob patterns of files that should be excluded from the included files.
+    #[arg(short = 'x', long)]
+    pub exclude: Vec<String>,
+
+    /// Name of the language the query is written for, files of other languages are skipped.
+    #[arg(short, long)]
+    pub language: Option<String>,
+
+    /// Tree Sitter query to execute.
+    #[arg(required_unless_present = "query_file", conflicts_with = "query_file")]
+    pub query: Option<String>,
+
+    /// Path to a file containing the Tree Sitter query to execute.
+    #[arg(short = 'f', long)]
+    pub query_file: Option<PathBuf>,
+
+    /// Format matches are printed in.
+    #[arg(short, long, value_enum, default_value_t = QueryOutputFormat::Occurrences)]
+    pub output: QueryOutputFormat,
+
+    /// Omits files without any matches when printing JSON.
+    #[arg(long)]
+    pub require_matches: bool,
+
+    /// Includes files ignored by `.gitignore`, `.ignore` and git excludes.
+    #[arg(long)]
+    pub no_ignore: bool,
+}
+
+/// Formats of the [`AntennaOutputMode`](antenna::configuration::AntennaOutputMode) variants
+/// that can be printed to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum QueryOutputFormat {
+    Json,
+    Csv,
+    Sarif,
+    Occurrences,
+}
+
+/// Parses a range of rows, either a single `ROW` or `START:END`.
+fn parse_row_range(range: &str) -> Result<(usize, usize), String> {
+    let parse = |x: &str| {
+        x.trim()
+            .parse::<usize>()
+            .map_err(|err| format!("`{x}` is not a row: {err}"))
+    };
+
+    match range.split_once(':') {
+        Some((start, end)) => Ok((parse(start)?, parse(end)?)),
+        None => parse(range).map(|x| (x, x)),
+    }
 }
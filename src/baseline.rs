@@ -0,0 +1,185 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::Node;
+
+use crate::{hash::StableHasher, out, AntennaError, AntennaResult};
+
+/// Version of the baseline file format written by this version of antenna.
+const VERSION: u32 = 1;
+
+/// A snapshot of the matches of a set of queries, used to report only matches that are not part
+/// of the snapshot.
+///
+/// Matches are identified by their query name, path and [fingerprint](fingerprint), so that
+/// matches which merely moved within their file are still recognized.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Baseline {
+    pub version: u32,
+    pub entries: Vec<BaselineEntry>,
+}
+
+/// The number of matches of a query in a file sharing a [fingerprint](fingerprint).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub struct BaselineEntry {
+    pub query: String,
+    pub path: PathBuf,
+    pub fingerprint: String,
+    pub count: usize,
+}
+
+impl Default for Baseline {
+    fn default() -> Self {
+        Self {
+            version: VERSION,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl Baseline {
+    /// Constructs a [`Baseline`] from the matches of the given `out_queries`, suppressed matches
+    /// are not part of the baseline.
+    pub fn from_out_queries<'a, I>(out_queries: I) -> Self
+    where
+        I: IntoIterator<Item = &'a out::Query<'a>>,
+    {
+        let mut counts = BTreeMap::<(&str, &Path, &str), usize>::new();
+
+        for out_query in out_queries {
+            for out_match in &out_query.matches {
+                let key = (
+                    out_query.name.as_ref(),
+                    out_query.path.as_ref(),
+                    out_match.fingerprint.as_str(),
+                );
+
+                *counts.entry(key).or_default() += 1;
+            }
+        }
+
+        let entries = counts
+            .into_iter()
+            .map(|((query, path, fingerprint), count)| {
+                BaselineEntry {
+                    query: query.to_owned(),
+                    path: path.to_path_buf(),
+                    fingerprint: fingerprint.to_owned(),
+                    count,
+                }
+            })
+            .collect();
+
+        Self {
+            version: VERSION,
+            entries,
+        }
+    }
+
+    /// Reads a [`Baseline`] from the JSON file at the given `path`.
+    ///
+    /// If the file was written in a different format version this function will return an
+    /// [`AntennaError`].
+    pub fn read<P>(path: P) -> AntennaResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let baseline: Self = serde_json::from_str(&fs::read_to_string(&path)?)?;
+
+        match baseline.version == VERSION {
+            true => Ok(baseline),
+            false => {
+                Err(AntennaError::Antenna {
+                    message: format!(
+                        "Baseline `{:?}` has version {}, expected version {VERSION}",
+                        path.as_ref(),
+                        baseline.version
+                    ),
+                })
+            },
+        }
+    }
+
+    /// Writes the [`Baseline`] to the file at the given `path` as JSON.
+    pub fn write<P>(&self, path: P) -> AntennaResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        out::write_file_atomically(path, |file| Ok(serde_json::to_writer_pretty(file, self)?))
+    }
+
+    /// Removes the matches of the given `out_queries` that are part of the [`Baseline`], leaving
+    /// only new matches. Returns the number of removed matches.
+    ///
+    /// If a file has more matches sharing a fingerprint than the baseline, the surplus matches are
+    /// considered new.
+    pub fn retain_new(&self, out_queries: &mut [out::Query]) -> usize {
+        let mut remaining = HashMap::<&str, HashMap<&Path, HashMap<&str, usize>>>::new();
+
+        for entry in &self.entries {
+            *remaining
+                .entry(&entry.query)
+                .or_default()
+                .entry(&entry.path)
+                .or_default()
+                .entry(&entry.fingerprint)
+                .or_default() += entry.count;
+        }
+
+        let mut removed = 0;
+
+        for out_query in out_queries {
+            let Some(fingerprints) = remaining
+                .get_mut(out_query.name.as_ref())
+                .and_then(|x| x.get_mut(out_query.path.as_ref()))
+            else {
+                continue;
+            };
+
+            out_query.matches.retain(|out_match| {
+                match fingerprints.get_mut(out_match.fingerprint.as_str()) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        removed += 1;
+                        false
+                    },
+
+                    _ => true,
+                }
+            });
+        }
+
+        removed
+    }
+}
+
+/// Computes the fingerprint of a match from the names, text and kinds of its captures and the kinds
+/// of their parents, parsed from the given `content`.
+///
+/// The fingerprint does not depend on the position of the match, and whitespace within the text of
+/// captures is normalized, so re-indenting or moving code does not change it.
+pub fn fingerprint<'a, I>(captures: I, content: &[u8]) -> String
+where
+    I: IntoIterator<Item = (&'a str, Node<'a>)>,
+{
+    let mut hasher = StableHasher::default();
+
+    for (name, node) in captures {
+        let text = content[node.byte_range()]
+            .split(u8::is_ascii_whitespace)
+            .filter(|x| !x.is_empty())
+            .collect::<Vec<_>>()
+            .join(&b' ');
+
+        hasher.write_field(name.as_bytes());
+        hasher.write_field(&text);
+        hasher.write_field(node.kind().as_bytes());
+        hasher.write_field(node.parent().map_or("", |x| x.kind()).as_bytes());
+    }
+
+    hasher.finish_hex()
+}
@@ -0,0 +1,244 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    configuration::{AntennaQuery, AntennaQueryText},
+    hash::StableHasher,
+    out, AntennaResult, RecognizedLanguage,
+};
+
+/// Version of the cache file format written by this version of antenna.
+const VERSION: u32 = 2;
+
+/// Name of the file within the cache directory holding the cached results.
+const FILE_NAME: &str = "results.json";
+
+/// A persistent cache of query results, allowing files whose content, query and grammar are
+/// unchanged since a previous run to be skipped without being parsed.
+///
+/// Results are keyed by a [key](Cache::key) covering everything they depend on, so entries are
+/// invalidated automatically once a file, query, grammar or antenna itself changes. Entries
+/// that were superseded or whose file no longer exists are dropped when the cache is
+/// [saved](Cache::save).
+#[derive(Debug)]
+pub struct Cache {
+    directory: PathBuf,
+    previous: HashMap<String, CacheEntry>,
+    current: Mutex<HashMap<String, CacheEntry>>,
+    grammars: Mutex<HashMap<RecognizedLanguage, String>>,
+}
+
+/// The matches of a query in a single file, as stored in a [`Cache`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CacheEntry {
+    pub query: String,
+    pub path: PathBuf,
+    pub matches: Vec<out::Match>,
+    pub suppressed: Vec<out::Match>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct CacheFile {
+    version: u32,
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl CacheEntry {
+    /// Constructs a [`CacheEntry`] from the matches of the given [`out::Query`].
+    pub fn from_out_query(out_query: &out::Query) -> Self {
+        Self {
+            query: out_query.name.to_string(),
+            path: out_query.path.to_path_buf(),
+            matches: out_query.matches.clone(),
+            suppressed: out_query.suppressed.clone(),
+        }
+    }
+
+    /// Converts the [`CacheEntry`] into the [`out::Query`] it was constructed from, named after
+    /// the query of the given `name` that retrieved it.
+    pub fn into_out_query(self, name: &str) -> out::Query<'static> {
+        out::Query {
+            name: name.to_owned().into(),
+            path: self.path.into(),
+            matches: self.matches,
+            suppressed: self.suppressed,
+        }
+    }
+}
+
+impl Cache {
+    /// Opens the [`Cache`] stored in the given `directory`, which is created once the cache is
+    /// [saved](Cache::save).
+    ///
+    /// A missing cache, or one written by a different version of antenna, is treated as empty.
+    pub fn open<P>(directory: P) -> AntennaResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let directory = directory.as_ref().to_path_buf();
+
+        let previous = match fs::read_to_string(directory.join(FILE_NAME)) {
+            Ok(text) => {
+                serde_json::from_str::<CacheFile>(&text)
+                    .ok()
+                    .filter(|x| x.version == VERSION)
+                    .map(|x| x.entries)
+                    .unwrap_or_default()
+            },
+
+            Err(err) if err.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(Self {
+            directory,
+            previous,
+            current: Mutex::default(),
+            grammars: Mutex::default(),
+        })
+    }
+
+    /// Computes the key of the results of the given [`AntennaQuery`] in the file at the given
+    /// `path`, with the given `content` parsed as the given `language`.
+    ///
+    /// The key covers the sources antenna was built from, the query name, text and message, the
+    /// path and content of the file, and the grammar of its language as well as of every
+    /// language the query has a variant for, as code of those languages may be injected into
+    /// the file.
+    pub fn key(
+        &self,
+        antenna_query: &AntennaQuery,
+        path: &Path,
+        content: &[u8],
+        language: RecognizedLanguage,
+    ) -> String {
+        let mut hasher = StableHasher::default();
+
+        hasher.write_field(env!("ANTENNA_BUILD_ID").as_bytes());
+        hasher.write_field(antenna_query.name.as_bytes());
+        hasher.write_field(path.to_string_lossy().as_bytes());
+        hasher.write_field(content);
+        hasher.write_field(language.name().as_bytes());
+        hasher.write_field(self.grammar_fingerprint(language).as_bytes());
+        hasher.write_field(
+            antenna_query
+                .message
+                .as_deref()
+                .unwrap_or_default()
+                .as_bytes(),
+        );
+
+        match &antenna_query.query {
+            AntennaQueryText::Shared(text) => hasher.write_field(text.as_bytes()),
+            AntennaQueryText::PerLanguage(variants) => {
+                for (name, text) in variants {
+                    let fingerprint = RecognizedLanguage::from_name(name)
+                        .map(|x| self.grammar_fingerprint(x))
+                        .unwrap_or_default();
+
+                    hasher.write_field(name.as_bytes());
+                    hasher.write_field(fingerprint.as_bytes());
+                    hasher.write_field(text.as_bytes());
+                }
+            },
+        }
+
+        hasher.finish_hex()
+    }
+
+    /// Retrieves the [`CacheEntry`] of the given `key` stored by a previous run, retaining it
+    /// when the cache is saved.
+    pub fn get(&self, key: &str) -> Option<CacheEntry> {
+        let entry = self.previous.get(key)?;
+
+        self.current
+            .lock()
+            .unwrap()
+            .insert(key.to_owned(), entry.clone());
+
+        Some(entry.clone())
+    }
+
+    /// Stores the given [`CacheEntry`] under the given `key`.
+    pub fn insert(&self, key: String, entry: CacheEntry) {
+        self.current.lock().unwrap().insert(key, entry);
+    }
+
+    /// Writes every entry retrieved or stored during this run to the cache directory, along
+    /// with the entries of the previous run for queries and files that were not part of this
+    /// run, as long as their file still exists.
+    pub fn save(&self) -> AntennaResult<()> {
+        let mut entries = self.current.lock().unwrap().clone();
+
+        let seen = entries
+            .values()
+            .map(|x| (x.query.as_str(), x.path.as_path()))
+            .collect::<HashSet<_>>();
+
+        let retained = self
+            .previous
+            .iter()
+            .filter(|(_, x)| !seen.contains(&(x.query.as_str(), x.path.as_path())))
+            .filter(|(_, x)| x.path.is_file())
+            .map(|(key, x)| (key.clone(), x.clone()))
+            .collect::<Vec<_>>();
+
+        entries.extend(retained);
+
+        fs::create_dir_all(&self.directory)?;
+
+        let cache_file = CacheFile {
+            version: VERSION,
+            entries,
+        };
+
+        out::write_file_atomically(self.directory.join(FILE_NAME), |file| {
+            Ok(serde_json::to_writer(file, &cache_file)?)
+        })
+    }
+
+    /// Computes a fingerprint of the grammar of the given `language` from the versions of the
+    /// Tree Sitter crates antenna was built with, the hash of the library a runtime-loaded
+    /// grammar was loaded from, and its ABI version, node kinds and field names, which changes
+    /// whenever the grammar is upgraded.
+    fn grammar_fingerprint(&self, language: RecognizedLanguage) -> String {
+        let mut grammars = self.grammars.lock().unwrap();
+
+        grammars
+            .entry(language)
+            .or_insert_with(|| {
+                let grammar = language.as_tree_sitter_language();
+                let mut hasher = StableHasher::default();
+
+                hasher.write_field(env!("ANTENNA_GRAMMAR_VERSIONS").as_bytes());
+
+                let library = crate::grammar::fingerprint(language.name()).unwrap_or_default();
+                hasher.write_field(library.as_bytes());
+
+                hasher.write_field(grammar.version().to_string().as_bytes());
+
+                for id in 0..grammar.node_kind_count() as u16 {
+                    let kind = grammar.node_kind_for_id(id).unwrap_or_default();
+                    let named = grammar.node_kind_is_named(id);
+
+                    hasher.write_field(kind.as_bytes());
+                    hasher.write_field(&[u8::from(named)]);
+                }
+
+                for id in 1..=grammar.field_count() as u16 {
+                    let field = grammar.field_name_for_id(id).unwrap_or_default();
+                    hasher.write_field(field.as_bytes());
+                }
+
+                hasher.finish_hex()
+            })
+            .clone()
+    }
+}
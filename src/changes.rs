@@ -0,0 +1,210 @@
+use std::{
+    collections::HashMap,
+    ops::Range,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{out, AntennaError, AntennaResult};
+
+/// The lines changed in each file of a git repository, relative to a revision.
+///
+/// Lines are zero-based and refer to the current content of each file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Changes {
+    files: HashMap<PathBuf, Vec<Range<usize>>>,
+}
+
+impl Changes {
+    /// Collects the changes of the working tree of the git repository containing the current
+    /// directory relative to the given `revision`, including staged, unstaged and untracked
+    /// changes. Paths are relative to the current directory.
+    ///
+    /// If `git` fails, for example as the `revision` does not exist, this function will return an
+    /// [`AntennaError`].
+    pub fn since(revision: &str) -> AntennaResult<Self> {
+        let diff = git(&[
+            "diff",
+            "--unified=0",
+            "--no-color",
+            "--no-ext-diff",
+            "--relative",
+            "--src-prefix=a/",
+            "--dst-prefix=b/",
+            revision,
+            "--",
+        ])?;
+
+        let mut changes = Self::from_diff(&diff);
+        let untracked = git(&["ls-files", "--others", "--exclude-standard"])?;
+
+        for path in untracked.lines().filter(|x| !x.is_empty()) {
+            let whole_file = std::iter::once(0..usize::MAX).collect();
+            changes.files.insert(PathBuf::from(path), whole_file);
+        }
+
+        Ok(changes)
+    }
+
+    /// Parses [`Changes`] from a unified diff, such as the output of `git diff --unified=0`.
+    ///
+    /// Pure deletions mark the lines surrounding the deleted lines as changed, while deleted files
+    /// are omitted. The body of each hunk is skipped according to the line counts of its header,
+    /// so changed lines resembling file headers are not mistaken for them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use antenna::changes::Changes;
+    ///
+    /// let changes = Changes::from_diff(
+    ///     "diff --git a/src/main.rs b/src/main.rs\n\
+    ///      --- a/src/main.rs\n\
+    ///      +++ b/src/main.rs\n\
+    ///      @@ -10,0 +11,2 @@ fn main() {\n\
+    ///      +    let a = 1;\n\
+    ///      +++ x\n\
+    ///      @@ -20 +22 @@ fn main() {\n\
+    ///      -    a.unwrap();\n\
+    ///      +    a.expect(\"a\");\n",
+    /// );
+    ///
+    /// assert!(changes.overlaps("src/main.rs", 11, 11));
+    /// assert!(!changes.overlaps("src/main.rs", 12, 14));
+    /// assert!(changes.overlaps("src/main.rs", 21, 21));
+    /// assert!(!changes.overlaps("src/lib.rs", 11, 11));
+    /// ```
+    pub fn from_diff(diff: &str) -> Self {
+        let mut files = HashMap::<PathBuf, Vec<Range<usize>>>::new();
+        let mut path = None;
+        let mut after_old_header = false;
+        let (mut old_remaining, mut new_remaining) = (0_usize, 0_usize);
+
+        for line in diff.lines() {
+            if old_remaining > 0 || new_remaining > 0 {
+                match line.as_bytes().first() {
+                    Some(b'-') => old_remaining = old_remaining.saturating_sub(1),
+                    Some(b'+') => new_remaining = new_remaining.saturating_sub(1),
+                    Some(b'\\') => {},
+                    _ => {
+                        old_remaining = old_remaining.saturating_sub(1);
+                        new_remaining = new_remaining.saturating_sub(1);
+                    },
+                }
+
+                continue;
+            }
+
+            let is_old_header = line.starts_with("--- ");
+
+            if let Some(target) = line.strip_prefix("+++ ").filter(|_| after_old_header) {
+                path = target.strip_prefix("b/").map(PathBuf::from);
+            }
+
+            after_old_header = is_old_header;
+
+            let Some(hunk) = line.strip_prefix("@@ ") else {
+                continue;
+            };
+
+            let counts = |prefix: char| {
+                hunk.split(' ')
+                    .find_map(|x| x.strip_prefix(prefix))
+                    .and_then(parse_hunk_range)
+            };
+
+            let (Some(old), Some(new)) = (counts('-'), counts('+')) else {
+                continue;
+            };
+
+            old_remaining = old.len;
+            new_remaining = new.len;
+
+            if let Some(path) = &path {
+                files.entry(path.clone()).or_default().push(new.range);
+            }
+        }
+
+        Self { files }
+    }
+
+    /// Determines whether the file at the given `path` has any changes.
+    pub fn contains_file<P>(&self, path: P) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        self.files.contains_key(path.as_ref())
+    }
+
+    /// Determines whether any of the zero-based lines from `start_line` to `end_line` inclusive
+    /// of the file at the given `path` are changed.
+    pub fn overlaps<P>(&self, path: P, start_line: usize, end_line: usize) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        self.files
+            .get(path.as_ref())
+            .is_some_and(|x| x.iter().any(|x| x.start <= end_line && start_line < x.end))
+    }
+
+    /// Removes the matches, including suppressed matches, of the given `out_queries` which do not
+    /// overlap any changed lines.
+    pub fn retain_changed(&self, out_queries: &mut [out::Query]) {
+        for out_query in out_queries {
+            let path = out_query.path.as_ref();
+
+            let is_changed = |out_match: &out::Match| {
+                out_match
+                    .captures
+                    .iter()
+                    .any(|x| self.overlaps(path, x.start_line, x.end_line))
+            };
+
+            out_query.matches.retain(is_changed);
+            out_query.suppressed.retain(is_changed);
+        }
+    }
+}
+
+/// A side of a hunk header, such as `11,2` in `@@ -10,0 +11,2 @@`.
+struct HunkRange {
+    /// The zero-based range of lines the hunk is considered to change, where empty hunks span the
+    /// lines surrounding them.
+    range: Range<usize>,
+
+    /// The number of lines of the hunk's body on this side.
+    len: usize,
+}
+
+/// Parses a side of a hunk header, such as `11,2` in `@@ -10,0 +11,2 @@`.
+fn parse_hunk_range(range: &str) -> Option<HunkRange> {
+    let (start, count) = match range.split_once(',') {
+        Some((start, count)) => (start.parse::<usize>().ok()?, count.parse::<usize>().ok()?),
+        None => (range.parse::<usize>().ok()?, 1),
+    };
+
+    let range = match count {
+        0 => start.saturating_sub(1)..start + 1,
+        _ => start.saturating_sub(1)..start.saturating_sub(1) + count,
+    };
+
+    Some(HunkRange { range, len: count })
+}
+
+/// Executes `git` with the given `args`, returning its standard output.
+fn git(args: &[&str]) -> AntennaResult<String> {
+    let output = Command::new("git").args(args).output()?;
+
+    match output.status.success() {
+        true => Ok(String::from_utf8(output.stdout)?),
+        false => {
+            Err(AntennaError::Antenna {
+                message: format!(
+                    "`git {}` failed: {}",
+                    args[0],
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            })
+        },
+    }
+}
@@ -0,0 +1,41 @@
+use antenna::{
+    baseline::Baseline, cache::Cache, configuration::AntennaConfiguration,
+    process::index::Indexer, AntennaResult,
+};
+use rayon::prelude::*;
+
+use crate::args::BaselineArguments;
+
+/// Executes every query in the given [`configuration`](AntennaConfiguration), writing a
+/// [`Baseline`] of their matches to the path given in the [`BaselineArguments`], reading and
+/// storing results in the given [`Cache`] if any.
+pub fn execute(
+    configuration: AntennaConfiguration,
+    arguments: BaselineArguments,
+    cache: Option<Cache>,
+) -> AntennaResult<()> {
+    let indexer = match cache {
+        Some(cache) => Indexer::default().with_cache(cache),
+        None => Indexer::default(),
+    };
+
+    let (query_cache, indexer) = super::prepare(&configuration, indexer)?;
+
+    let out_queries = configuration
+        .queries
+        .par_iter()
+        .map(|x| antenna::process::execute_antenna_query(x, &indexer, &query_cache))
+        .collect::<AntennaResult<Vec<_>>>()?;
+
+    if let Some(cache) = indexer.cache() {
+        cache.save()?;
+    }
+
+    let baseline = Baseline::from_out_queries(out_queries.iter().flatten());
+    let match_count = baseline.entries.iter().map(|x| x.count).sum::<usize>();
+
+    baseline.write(&arguments.output)?;
+    eprintln!("Wrote {match_count} matches to {:?}", arguments.output);
+
+    Ok(())
+}
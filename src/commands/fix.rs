@@ -0,0 +1,117 @@
+use std::{collections::BTreeMap, fs, io::prelude::*, path::PathBuf, process::ExitCode};
+
+use antenna::{
+    configuration::AntennaConfiguration,
+    fix::{self, Edit},
+    out,
+    process::index::Indexer,
+    AntennaResult,
+};
+use rayon::prelude::*;
+
+use crate::args::FixArguments;
+
+/// Executes every query in the given [`configuration`](AntennaConfiguration) with a `fix`,
+/// replacing the source of each of their matches with the rendered fix.
+///
+/// With `--dry-run` files are left untouched and a unified diff of the fixes is printed instead.
+/// Fixes overlapping a fix that was applied are skipped and reported as conflicts.
+///
+/// Returns [`ExitCode::FAILURE`] if any fix was skipped due to a conflict.
+pub fn execute(
+    configuration: AntennaConfiguration,
+    arguments: FixArguments,
+) -> AntennaResult<ExitCode> {
+    let (query_cache, indexer) = super::prepare(&configuration, Indexer::default())?;
+
+    let executed = configuration
+        .queries
+        .par_iter()
+        .filter(|x| x.fix.is_some())
+        .map(|x| {
+            antenna::process::execute_antenna_query(x, &indexer, &query_cache)
+                .map(|out_queries| (x, out_queries))
+        })
+        .collect::<AntennaResult<Vec<_>>>()?;
+
+    let mut edits_by_path = BTreeMap::<PathBuf, Vec<Edit>>::new();
+
+    for (antenna_query, out_queries) in &executed {
+        for out_query in out_queries {
+            let Some(file) = indexer.get_file_by_path(&out_query.path) else {
+                continue;
+            };
+
+            let content = String::from_utf8(file.content.clone())?;
+            let edits = Edit::from_out_query(antenna_query, out_query, &content);
+
+            if !edits.is_empty() {
+                edits_by_path
+                    .entry(out_query.path.to_path_buf())
+                    .or_default()
+                    .extend(edits);
+            }
+        }
+    }
+
+    let mut applied_count = 0;
+    let mut conflict_count = 0;
+    let mut stdout = std::io::stdout().lock();
+
+    for (path, edits) in edits_by_path {
+        let Some(file) = indexer.get_file_by_path(&path) else {
+            continue;
+        };
+
+        let content = String::from_utf8(file.content.clone())?;
+        let fixed = fix::apply(&content, edits);
+
+        for conflict in &fixed.conflicts {
+            let line = content[..conflict.skipped.range.start]
+                .matches('\n')
+                .count()
+                + 1;
+
+            eprintln!(
+                "conflict: {path:?}:{line}: fix of `{}` overlaps fix of `{}`, skipped",
+                conflict.skipped.query, conflict.applied.query
+            );
+        }
+
+        applied_count += fixed.applied.len();
+        conflict_count += fixed.conflicts.len();
+
+        if fixed.content == content {
+            continue;
+        }
+
+        match arguments.dry_run {
+            true => {
+                write!(
+                    stdout,
+                    "{}",
+                    fix::unified_diff(&path, &content, &fixed.content)
+                )?
+            },
+            false => {
+                let permissions = fs::metadata(&path)?.permissions();
+
+                out::write_file_atomically(&path, |file| {
+                    file.write_all(fixed.content.as_bytes())?;
+                    file.set_permissions(permissions)?;
+                    Ok(())
+                })?;
+            },
+        }
+    }
+
+    match arguments.dry_run {
+        true => eprintln!("Would apply {applied_count} fixes, {conflict_count} conflicting"),
+        false => eprintln!("Applied {applied_count} fixes, {conflict_count} conflicting"),
+    }
+
+    match conflict_count {
+        0 => Ok(ExitCode::SUCCESS),
+        _ => Ok(ExitCode::FAILURE),
+    }
+}
@@ -0,0 +1,393 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    io::{self, prelude::*},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use antenna::{
+    configuration::{AntennaConfiguration, AntennaQuery, AntennaSeverity},
+    detect::LanguageMap,
+    out,
+    process::{discover, index::IndexedFile, query::QueryCache},
+    AntennaError, AntennaResult, RecognizedLanguage,
+};
+use serde_json::{json, Value};
+
+/// JSON-RPC error code of a message whose content is not valid JSON.
+const PARSE_ERROR: i64 = -32700;
+
+/// JSON-RPC error code of a request whose method is not supported.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// LSP message type of errors sent via `window/logMessage`.
+const LOG_ERROR: u8 = 1;
+
+/// Speaks the Language Server Protocol over stdin and stdout until the client sends `exit`,
+/// publishing the matches of the queries in the given [`configuration`](AntennaConfiguration)
+/// as diagnostics of every open document.
+///
+/// Documents are parsed from the content held by the editor rather than from disk, and are
+/// re-parsed incrementally as they change. A query applies to a document if its path, relative to
+/// the current directory, is matched by the query's `include` and `exclude` patterns.
+///
+/// Returns [`ExitCode::FAILURE`] if the client exits without requesting a shutdown first.
+pub fn execute(configuration: AntennaConfiguration) -> AntennaResult<ExitCode> {
+    antenna::grammar::load_grammars(&configuration)?;
+
+    let mut server = Server {
+        query_cache: QueryCache::from_configuration(&configuration)?,
+        language_map: LanguageMap::from_configuration(&configuration)?,
+        current_directory: std::env::current_dir()?,
+        documents: HashMap::new(),
+        configuration,
+    };
+
+    let mut stdin = io::stdin().lock();
+    let mut shutdown = false;
+
+    while let Some(message) = read_message(&mut stdin)? {
+        let message = match message {
+            Ok(message) => message,
+            Err(err) => {
+                let error = json!({
+                    "code": PARSE_ERROR,
+                    "message": format!("Message is not valid JSON: {err}"),
+                });
+
+                write_message(&json!({ "jsonrpc": "2.0", "id": null, "error": error }))?;
+                continue;
+            },
+        };
+
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+
+        match (method, message.get("id")) {
+            ("initialize", Some(id)) => {
+                let capabilities = json!({
+                    "capabilities": {
+                        "textDocumentSync": { "openClose": true, "change": 1 },
+                    },
+                    "serverInfo": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                });
+
+                respond(id, capabilities)?;
+            },
+
+            ("shutdown", Some(id)) => {
+                shutdown = true;
+                respond(id, Value::Null)?;
+            },
+
+            ("exit", _) => break,
+
+            ("textDocument/didOpen", None) => {
+                let document = &params["textDocument"];
+
+                if let (Some(uri), Some(text)) =
+                    (document["uri"].as_str(), document["text"].as_str())
+                {
+                    server.analyze(uri, text)?;
+                }
+            },
+
+            ("textDocument/didChange", None) => {
+                let uri = params["textDocument"]["uri"].as_str();
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|x| x.last())
+                    .and_then(|x| x["text"].as_str());
+
+                if let (Some(uri), Some(text)) = (uri, text) {
+                    server.analyze(uri, text)?;
+                }
+            },
+
+            ("textDocument/didClose", None) => {
+                if let Some(uri) = params["textDocument"]["uri"].as_str() {
+                    server.documents.remove(uri);
+                    publish_diagnostics(uri, Vec::new())?;
+                }
+            },
+
+            (_, Some(id)) => {
+                let error = json!({
+                    "code": METHOD_NOT_FOUND,
+                    "message": format!("Method `{method}` is not supported"),
+                });
+
+                write_message(&json!({ "jsonrpc": "2.0", "id": id, "error": error }))?;
+            },
+
+            (_, None) => {},
+        }
+    }
+
+    match shutdown {
+        true => Ok(ExitCode::SUCCESS),
+        false => Ok(ExitCode::FAILURE),
+    }
+}
+
+/// State of the language server, holding the parsed versions of every open document.
+struct Server {
+    configuration: AntennaConfiguration,
+    query_cache: QueryCache,
+    language_map: LanguageMap,
+    current_directory: PathBuf,
+
+    /// Open documents keyed by their URI, each parsed once per language queries parse it as.
+    documents: HashMap<String, HashMap<RecognizedLanguage, IndexedFile>>,
+}
+
+impl Server {
+    /// Parses the document of the given `uri` with the given `text` and executes every query
+    /// applying to it, publishing their matches as diagnostics.
+    ///
+    /// Errors of individual queries are logged to the client rather than ending the server.
+    fn analyze(&mut self, uri: &str, text: &str) -> AntennaResult<()> {
+        let Some(path) =
+            path_from_uri(uri).map(|x| super::relative_path(&self.current_directory, &x))
+        else {
+            return Ok(());
+        };
+
+        let previous = self.documents.remove(uri).unwrap_or_default();
+        let detected = self.language_map.detect(&path, text.as_bytes());
+        let line_starts = antenna::fix::line_starts(text);
+
+        let mut files = HashMap::new();
+        let mut diagnostics = Vec::new();
+
+        for antenna_query in &self.configuration.queries {
+            let executed = (|| {
+                if !discover::is_included(antenna_query, &path)? {
+                    return Ok(Vec::new());
+                }
+
+                let Some(language) = antenna_query.forced_language()?.or(detected) else {
+                    return Ok(Vec::new());
+                };
+
+                let file = match files.entry(language) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        entry.insert(IndexedFile::parse(
+                            &path,
+                            text.as_bytes().to_vec(),
+                            language,
+                            previous.get(&language),
+                        )?)
+                    },
+                };
+
+                if !file.is_queried_by(antenna_query) {
+                    return Ok(Vec::new());
+                }
+
+                let out_query = antenna::process::execute_antenna_query_on_file(
+                    antenna_query,
+                    file,
+                    &self.query_cache,
+                )?;
+
+                let diagnostics = out_query
+                    .matches
+                    .iter()
+                    .map(|x| diagnostic(antenna_query, x, text, &line_starts))
+                    .collect();
+
+                Ok::<_, AntennaError>(diagnostics)
+            })();
+
+            match executed {
+                Ok(executed) => diagnostics.extend(executed),
+                Err(err) => {
+                    let message = format!(
+                        "`{}` failed on {path:?}: {}",
+                        antenna_query.name,
+                        super::describe(&err)
+                    );
+
+                    notify(
+                        "window/logMessage",
+                        json!({ "type": LOG_ERROR, "message": message }),
+                    )?;
+                },
+            }
+        }
+
+        self.documents.insert(uri.to_owned(), files);
+        publish_diagnostics(uri, diagnostics)
+    }
+}
+
+/// Converts a [`Match`](out::Match) of an [`AntennaQuery`] into an LSP diagnostic spanning all of
+/// its captures, with the query's severity and the match's message.
+fn diagnostic(
+    antenna_query: &AntennaQuery,
+    out_match: &out::Match,
+    text: &str,
+    line_starts: &[usize],
+) -> Value {
+    let start = out_match
+        .captures
+        .iter()
+        .map(|x| (x.start_line, x.start_column))
+        .min()
+        .unwrap_or_default();
+
+    let end = out_match
+        .captures
+        .iter()
+        .map(|x| (x.end_line, x.end_column))
+        .max()
+        .unwrap_or_default();
+
+    let severity = match antenna_query.severity {
+        AntennaSeverity::Error => 1,
+        AntennaSeverity::Warning => 2,
+        AntennaSeverity::Info => 3,
+    };
+
+    let message = out_match
+        .message
+        .clone()
+        .unwrap_or_else(|| format!("Matched by `{}`", antenna_query.name));
+
+    json!({
+        "range": {
+            "start": position(text, line_starts, start),
+            "end": position(text, line_starts, end),
+        },
+        "severity": severity,
+        "code": antenna_query.name,
+        "source": env!("CARGO_PKG_NAME"),
+        "message": message,
+    })
+}
+
+/// Converts a zero-based line and byte column, as reported by Tree Sitter, into an LSP position,
+/// whose character offsets count UTF-16 code units.
+fn position(text: &str, line_starts: &[usize], (line, column): (usize, usize)) -> Value {
+    let character = line_starts
+        .get(line)
+        .and_then(|&start| text.get(start..start + column))
+        .map_or(column, |x| x.encode_utf16().count());
+
+    json!({ "line": line, "character": character })
+}
+
+/// Converts a `file://` URI into the path it refers to, decoding percent-encoded bytes.
+///
+/// Returns [`None`] if the URI does not refer to a local file.
+fn path_from_uri(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+
+    while i < encoded.len() {
+        let byte = match encoded[i] {
+            b'%' => {
+                let hex = std::str::from_utf8(encoded.get(i + 1..i + 3)?).ok()?;
+                i += 2;
+                u8::from_str_radix(hex, 16).ok()?
+            },
+
+            byte => byte,
+        };
+
+        decoded.push(byte);
+        i += 1;
+    }
+
+    String::from_utf8(decoded).ok().map(PathBuf::from)
+}
+
+/// Publishes the given `diagnostics` of the document of the given `uri`, replacing any previously
+/// published diagnostics.
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> AntennaResult<()> {
+    notify(
+        "textDocument/publishDiagnostics",
+        json!({ "uri": uri, "diagnostics": diagnostics }),
+    )
+}
+
+/// Sends the `result` of the request of the given `id`.
+fn respond(id: &Value, result: Value) -> AntennaResult<()> {
+    write_message(&json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+/// Sends a notification of the given `method` with the given `params`.
+fn notify(method: &str, params: Value) -> AntennaResult<()> {
+    write_message(&json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+}
+
+/// Reads a JSON-RPC message framed by a `Content-Length` header from the `reader`, returning the
+/// message, or the error encountered while parsing its content as JSON. Frames without a valid
+/// `Content-Length` header are skipped and reported via `window/logMessage`.
+///
+/// Returns [`None`] once the `reader` is exhausted, and an [`AntennaError`] if reading from it
+/// fails.
+fn read_message<R>(reader: &mut R) -> AntennaResult<Option<Result<Value, serde_json::Error>>>
+where
+    R: BufRead,
+{
+    let mut header = String::new();
+
+    loop {
+        let mut content_length = None;
+
+        loop {
+            header.clear();
+
+            if reader.read_line(&mut header)? == 0 {
+                return Ok(None);
+            }
+
+            let header = header.trim_end();
+
+            if header.is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse::<usize>().ok();
+                }
+            }
+        }
+
+        let Some(content_length) = content_length else {
+            notify(
+                "window/logMessage",
+                json!({
+                    "type": LOG_ERROR,
+                    "message": "Skipped a message without a valid `Content-Length` header",
+                }),
+            )?;
+
+            continue;
+        };
+
+        let mut content = vec![0; content_length];
+        reader.read_exact(&mut content)?;
+
+        return Ok(Some(serde_json::from_slice(&content)));
+    }
+}
+
+/// Writes a JSON-RPC `message` framed by a `Content-Length` header to stdout.
+fn write_message(message: &Value) -> AntennaResult<()> {
+    let content = serde_json::to_string(message)?;
+    let mut stdout = io::stdout().lock();
+
+    write!(stdout, "Content-Length: {}\r\n\r\n{content}", content.len())?;
+    stdout.flush()?;
+
+    Ok(())
+}
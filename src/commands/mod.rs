@@ -0,0 +1,61 @@
+use std::{
+    error::Error,
+    path::{Component, Path, PathBuf},
+};
+
+use antenna::{
+    configuration::AntennaConfiguration,
+    process::{index::Indexer, query::QueryCache},
+    AntennaError, AntennaResult,
+};
+
+pub mod baseline;
+pub mod fix;
+pub mod lsp;
+pub mod query;
+pub mod run;
+pub mod test;
+pub mod tree;
+pub mod watch;
+
+/// Loads the grammars, compiles the queries and indexes the files of the given
+/// [`configuration`](AntennaConfiguration) via the given [`Indexer`], printing any diagnostics
+/// collected while indexing.
+pub fn prepare(
+    configuration: &AntennaConfiguration,
+    indexer: Indexer,
+) -> AntennaResult<(QueryCache, Indexer)> {
+    antenna::grammar::load_grammars(configuration)?;
+
+    let query_cache = QueryCache::from_configuration(configuration)?;
+    let indexer = indexer.index(configuration)?;
+
+    for diagnostic in indexer.diagnostics() {
+        eprintln!("warning: {diagnostic}");
+    }
+
+    Ok((query_cache, indexer))
+}
+
+/// Describes an [`AntennaError`] along with the chain of errors that caused it.
+pub fn describe(error: &AntennaError) -> String {
+    let mut description = error.to_string();
+    let mut source = error.source();
+
+    while let Some(error) = source {
+        description.push_str(&format!(": {error}"));
+        source = error.source();
+    }
+
+    description
+}
+
+/// Converts a `path` into a path relative to the `current_directory`, in the form paths are
+/// indexed in and matched against `include` patterns.
+pub fn relative_path(current_directory: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(current_directory)
+        .unwrap_or(path)
+        .components()
+        .filter(|x| !matches!(x, Component::CurDir))
+        .collect()
+}
@@ -0,0 +1,120 @@
+use std::{collections::BTreeMap, fs};
+
+use antenna::{
+    configuration::{
+        AntennaConfiguration, AntennaErrorPolicy, AntennaQuery, AntennaQueryText,
+        AntennaSeverity,
+    },
+    out::sarif,
+    process::{index::Indexer, query::QueryCache},
+    AntennaError, AntennaResult, RecognizedLanguage,
+};
+
+use crate::args::{QueryArguments, QueryOutputFormat};
+
+/// Name given to the [`AntennaQuery`] constructed from the command line.
+const QUERY_NAME: &str = "query";
+
+/// Executes an ad-hoc query constructed from the given [`QueryArguments`], printing matches to
+/// stdout. The `grammars` and `languages` of the given `configuration` are used if present.
+pub fn execute(
+    configuration: Option<AntennaConfiguration>,
+    arguments: QueryArguments,
+    error_policy: AntennaErrorPolicy,
+) -> AntennaResult<()> {
+    let (grammars, languages) = match configuration {
+        Some(configuration) => {
+            antenna::grammar::load_grammars(&configuration)?;
+            (configuration.grammars, configuration.languages)
+        },
+
+        None => (Vec::new(), BTreeMap::new()),
+    };
+
+    let text = match (arguments.query, arguments.query_file) {
+        (Some(query), _) => query,
+        (None, Some(query_file)) => fs::read_to_string(query_file)?,
+        (None, None) => {
+            return Err(AntennaError::Antenna {
+                message: String::from("Either a query or a query file must be provided"),
+            })
+        },
+    };
+
+    let query = match arguments.language {
+        Some(language) => {
+            let language =
+                RecognizedLanguage::from_name(&language).ok_or(AntennaError::Antenna {
+                    message: format!("`{language}` is not a `RecognizedLanguage`"),
+                })?;
+
+            AntennaQueryText::PerLanguage(BTreeMap::from([(language.name().to_owned(), text)]))
+        },
+
+        None => AntennaQueryText::Shared(text),
+    };
+
+    let configuration = AntennaConfiguration {
+        error_policy,
+        languages,
+        grammars,
+        queries: vec![AntennaQuery {
+            name: String::from(QUERY_NAME),
+            include: arguments.include,
+            exclude: arguments.exclude,
+            respect_ignore: !arguments.no_ignore,
+            language: None,
+            query,
+            severity: AntennaSeverity::default(),
+            message: None,
+            fix: None,
+            max_matches: None,
+            output: None,
+            tests: Vec::new(),
+        }],
+    };
+
+    let query_cache = QueryCache::from_configuration(&configuration)?;
+    let indexer = Indexer::default().index(&configuration)?;
+
+    for diagnostic in indexer.diagnostics() {
+        eprintln!("warning: {diagnostic}");
+    }
+
+    let antenna_query = &configuration.queries[0];
+    let out_queries =
+        antenna::process::execute_antenna_query(antenna_query, &indexer, &query_cache)?;
+    let stdout = std::io::stdout().lock();
+
+    match arguments.output {
+        QueryOutputFormat::Occurrences => {
+            antenna::out::write_occurrences(stdout, QUERY_NAME, &out_queries)?;
+        },
+
+        QueryOutputFormat::Json => {
+            antenna::out::write_json(stdout, &out_queries, arguments.require_matches)?;
+            println!();
+        },
+
+        QueryOutputFormat::Csv => {
+            antenna::out::csv::write_csv(stdout, QUERY_NAME, &out_queries, true)?;
+        },
+
+        QueryOutputFormat::Sarif => {
+            let mut sarif_log = sarif::Log::default();
+
+            let options = sarif::RuleOptions {
+                id: QUERY_NAME,
+                name: QUERY_NAME,
+                message: "Matched by the query",
+                level: sarif::Level::default(),
+            };
+
+            sarif_log.push_rule(options, &out_queries);
+            serde_json::to_writer_pretty(stdout, &sarif_log)?;
+            println!();
+        },
+    }
+
+    Ok(())
+}
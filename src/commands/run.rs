@@ -0,0 +1,218 @@
+use std::{collections::BTreeMap, process::ExitCode};
+
+use antenna::{
+    baseline::Baseline,
+    cache::Cache,
+    changes::Changes,
+    configuration::{AntennaConfiguration, AntennaOutputMode, AntennaQuery, AntennaSeverity},
+    out::{self, sarif},
+    process::index::Indexer,
+    AntennaResult,
+};
+use rayon::prelude::*;
+
+/// Executes every query in the given [`configuration`](AntennaConfiguration), writing matches
+/// to each query's output modes.
+///
+/// If a [`Baseline`] is given, matches that are part of it are neither written nor counted, the
+/// same applies to matches outside of the given [`Changes`]. If a [`Cache`] is given, files with
+/// cached results are not parsed, and the cache is saved once every query was executed.
+///
+/// Returns [`ExitCode::FAILURE`] if a query exceeds its `max_matches`, or if a query with a
+/// severity of at least `fail_on` has any matches.
+pub fn execute(
+    configuration: AntennaConfiguration,
+    fail_on: Option<AntennaSeverity>,
+    baseline: Option<Baseline>,
+    changes: Option<Changes>,
+    cache: Option<Cache>,
+) -> AntennaResult<ExitCode> {
+    let indexer = match changes {
+        Some(changes) => Indexer::default().with_changes(changes),
+        None => Indexer::default(),
+    };
+
+    let indexer = match cache {
+        Some(cache) => indexer.with_cache(cache),
+        None => indexer,
+    };
+
+    let (query_cache, indexer) = super::prepare(&configuration, indexer)?;
+
+    let executed = configuration
+        .queries
+        .par_iter()
+        .map(|x| antenna::process::execute_antenna_query(x, &indexer, &query_cache))
+        .collect::<Vec<_>>();
+
+    let mut results = Vec::with_capacity(executed.len());
+
+    for out_queries in executed {
+        let mut out_queries = out_queries?;
+
+        if let Some(baseline) = &baseline {
+            baseline.retain_new(&mut out_queries);
+        }
+
+        if let Some(changes) = indexer.changes() {
+            changes.retain_changed(&mut out_queries);
+        }
+
+        results.push(out_queries);
+    }
+
+    if let Some(cache) = indexer.cache() {
+        cache.save()?;
+    }
+
+    let failures = report(&configuration, &results, fail_on, |_| true)?;
+
+    for failure in &failures {
+        eprintln!("error: {failure}");
+    }
+
+    match failures.is_empty() {
+        true => Ok(ExitCode::SUCCESS),
+        false => Ok(ExitCode::FAILURE),
+    }
+}
+
+/// Writes the matches of every query in the given [`configuration`](AntennaConfiguration) for
+/// which `is_affected` returns `true` to the query's output modes, where `results` holds the
+/// matches of each query in the order of the configuration's queries.
+///
+/// SARIF logs shared with an affected query are rewritten in full, including the matches of
+/// queries that are not affected.
+///
+/// Returns a description of every affected query exceeding its `max_matches`, or with a severity
+/// of at least `fail_on` and any matches.
+pub fn report<F>(
+    configuration: &AntennaConfiguration,
+    results: &[Vec<out::Query>],
+    fail_on: Option<AntennaSeverity>,
+    is_affected: F,
+) -> AntennaResult<Vec<String>>
+where
+    F: Fn(&AntennaQuery) -> bool,
+{
+    let queries = configuration.queries.iter().zip(results);
+
+    let mut sarif_logs = BTreeMap::<&str, sarif::Log>::new();
+    let mut failures = Vec::new();
+
+    for (antenna_query, out_queries) in queries.clone().filter(|(x, _)| is_affected(x)) {
+        failures.extend(check(antenna_query, out_queries, fail_on));
+
+        for output_mode in antenna_query.output.iter().flatten() {
+            match output_mode {
+                AntennaOutputMode::Occurrences => {
+                    let stdout = std::io::stdout().lock();
+                    out::write_occurrences(stdout, &antenna_query.name, out_queries)?;
+                },
+
+                AntennaOutputMode::Json {
+                    path,
+                    require_matches,
+                } => {
+                    out::write_file_atomically(path, |file| {
+                        out::write_json(file, out_queries, *require_matches)
+                    })?;
+                },
+
+                AntennaOutputMode::Csv { path, append } => {
+                    let name = &antenna_query.name;
+
+                    match append {
+                        false => {
+                            out::write_file_atomically(path, |file| {
+                                out::csv::write_csv(file, name, out_queries, true)
+                            })?;
+                        },
+
+                        true => {
+                            let file = out::open_file_for_append(path)?;
+                            let headers = file.metadata()?.len() == 0;
+
+                            out::csv::write_csv(file, name, out_queries, headers)?;
+                        },
+                    }
+                },
+
+                AntennaOutputMode::Sarif { path, .. } => {
+                    sarif_logs.entry(path).or_default();
+                },
+            }
+        }
+    }
+
+    for (antenna_query, out_queries) in queries {
+        for output_mode in antenna_query.output.iter().flatten() {
+            let AntennaOutputMode::Sarif {
+                path,
+                rule_id,
+                message,
+                level,
+            } = output_mode
+            else {
+                continue;
+            };
+
+            let Some(sarif_log) = sarif_logs.get_mut(path.as_str()) else {
+                continue;
+            };
+
+            let default_message = format!("Matched by `{}`", antenna_query.name);
+
+            let options = sarif::RuleOptions {
+                id: rule_id.as_deref().unwrap_or(&antenna_query.name),
+                name: &antenna_query.name,
+                message: message
+                    .as_deref()
+                    .or(antenna_query.message.as_deref())
+                    .unwrap_or(&default_message),
+                level: level.unwrap_or(antenna_query.severity.into()),
+            };
+
+            sarif_log.push_rule(options, out_queries);
+        }
+    }
+
+    for (path, sarif_log) in sarif_logs {
+        out::write_file_atomically(path, |file| {
+            Ok(serde_json::to_writer_pretty(file, &sarif_log)?)
+        })?;
+    }
+
+    Ok(failures)
+}
+
+/// Checks the matches of an [`AntennaQuery`] against its `max_matches` and the `fail_on`
+/// severity, returning a description of every failure.
+fn check(
+    antenna_query: &AntennaQuery,
+    out_queries: &[out::Query],
+    fail_on: Option<AntennaSeverity>,
+) -> Vec<String> {
+    let match_count = out_queries.iter().map(|x| x.matches.len()).sum::<usize>();
+    let mut failures = Vec::new();
+
+    if let Some(max_matches) = antenna_query.max_matches {
+        if match_count > max_matches {
+            failures.push(format!(
+                "`{}` has {match_count} matches, exceeding the maximum of {max_matches}",
+                antenna_query.name
+            ));
+        }
+    }
+
+    if let Some(fail_on) = fail_on {
+        if antenna_query.severity >= fail_on && match_count > 0 {
+            failures.push(format!(
+                "`{}` has {match_count} matches with a severity of `{}`",
+                antenna_query.name, antenna_query.severity
+            ));
+        }
+    }
+
+    failures
+}
@@ -0,0 +1,232 @@
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use antenna::{
+    configuration::{AntennaConfiguration, AntennaQuery, AntennaQueryTest, AntennaQueryText},
+    detect::LanguageMap,
+    fixture::{Fixture, Outcome},
+    process::{index::IndexedFile, query::QueryCache},
+    AntennaError, AntennaResult, RecognizedLanguage,
+};
+
+use crate::args::TestArguments;
+
+/// Executes the `tests` of every query in the given [`configuration`](AntennaConfiguration),
+/// printing the result of each test and the missing and unexpected captures of failed tests.
+///
+/// Tests are named `query::test`, only tests whose name contains the filter given in the
+/// [`TestArguments`] are executed.
+///
+/// Returns [`ExitCode::FAILURE`] if any test failed.
+pub fn execute(
+    configuration: AntennaConfiguration,
+    arguments: TestArguments,
+) -> AntennaResult<ExitCode> {
+    antenna::grammar::load_grammars(&configuration)?;
+
+    let query_cache = QueryCache::from_configuration(&configuration)?;
+    let language_map = LanguageMap::from_configuration(&configuration)?;
+
+    let tests = configuration
+        .queries
+        .iter()
+        .flat_map(|antenna_query| {
+            antenna_query
+                .tests
+                .iter()
+                .enumerate()
+                .map(move |(i, test)| {
+                    let name = test
+                        .name
+                        .clone()
+                        .or_else(|| test.path.as_ref().map(|x| x.display().to_string()))
+                        .unwrap_or_else(|| (i + 1).to_string());
+
+                    (
+                        format!("{}::{name}", antenna_query.name),
+                        antenna_query,
+                        test,
+                    )
+                })
+        })
+        .filter(|(name, ..)| {
+            arguments
+                .filter
+                .as_ref()
+                .is_none_or(|filter| name.contains(filter))
+        })
+        .collect::<Vec<_>>();
+
+    println!("running {} tests", tests.len());
+
+    let mut failures = Vec::new();
+
+    for (name, antenna_query, test) in &tests {
+        let failure = match run(antenna_query, test, name, &language_map, &query_cache) {
+            Ok(outcome) if outcome.is_success() => None,
+            Ok(outcome) => Some(describe_outcome(&outcome)),
+            Err(err) => Some(format!("error: {}", super::describe(&err))),
+        };
+
+        match failure {
+            None => println!("test {name} ... ok"),
+            Some(failure) => {
+                println!("test {name} ... FAILED");
+                failures.push((name, failure));
+            },
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+
+        for (name, failure) in &failures {
+            println!("\n---- {name} ----\n{failure}");
+        }
+    }
+
+    let passed = tests.len() - failures.len();
+
+    match failures.is_empty() {
+        true => {
+            println!("\ntest result: ok. {passed} passed; 0 failed");
+            Ok(ExitCode::SUCCESS)
+        },
+
+        false => {
+            println!(
+                "\ntest result: FAILED. {passed} passed; {} failed",
+                failures.len()
+            );
+            Ok(ExitCode::FAILURE)
+        },
+    }
+}
+
+/// Executes a single test of an [`AntennaQuery`] named `name`, comparing the captures of the
+/// query against the captures annotated in the test's code.
+fn run(
+    antenna_query: &AntennaQuery,
+    test: &AntennaQueryTest,
+    name: &str,
+    language_map: &LanguageMap,
+    query_cache: &QueryCache,
+) -> AntennaResult<Outcome> {
+    let (text, path) = match (&test.code, &test.path) {
+        (Some(code), None) => (code.clone(), inline_path(antenna_query, name)),
+        (None, Some(path)) => (fs::read_to_string(path)?, path.clone()),
+        _ => {
+            return Err(AntennaError::Antenna {
+                message: String::from("A test needs either `code` or `path`"),
+            })
+        },
+    };
+
+    let fixture = Fixture::parse(&text);
+    let language = language(antenna_query, test, &fixture, language_map)?;
+
+    let file = IndexedFile::parse(&path, fixture.code.clone().into_bytes(), language, None)?;
+    let out_query =
+        antenna::process::execute_antenna_query_on_file(antenna_query, &file, query_cache)?;
+
+    Ok(fixture.check(&out_query.matches))
+}
+
+/// Constructs the path the inline code of a test named `name` is parsed at, matching the first
+/// `include` pattern of the [`AntennaQuery`] so `#path-match?` predicates behave as they do for
+/// included files, e.g. `./src/**/*.js` becomes `src/name.js`.
+fn inline_path(antenna_query: &AntennaQuery, name: &str) -> PathBuf {
+    let Some(pattern) = antenna_query.include.first() else {
+        return PathBuf::from(name);
+    };
+
+    let pattern = pattern.strip_prefix("./").unwrap_or(pattern);
+
+    PathBuf::from(
+        pattern
+            .replace("**/", "")
+            .replace("**", name)
+            .replace('*', name)
+            .replace('?', "_"),
+    )
+}
+
+/// Determines the language the code of a test is parsed as, either the `language` of the test,
+/// the forced language of the [`AntennaQuery`], the language detected from the test's `path`, the
+/// language of the query's only variant, or the only language its `include` patterns refer to.
+fn language(
+    antenna_query: &AntennaQuery,
+    test: &AntennaQueryTest,
+    fixture: &Fixture,
+    language_map: &LanguageMap,
+) -> AntennaResult<RecognizedLanguage> {
+    if let Some(name) = &test.language {
+        return RecognizedLanguage::from_name(name).ok_or(AntennaError::Antenna {
+            message: format!("Test uses the unrecognized language `{name}`"),
+        });
+    }
+
+    let only_variant = || {
+        match &antenna_query.query {
+            AntennaQueryText::PerLanguage(variants) if variants.len() == 1 => {
+                variants
+                    .keys()
+                    .next()
+                    .and_then(|x| RecognizedLanguage::from_name(x))
+            },
+
+            _ => None,
+        }
+    };
+
+    let only_included = || {
+        let languages = QueryCache::include_languages(antenna_query, language_map);
+
+        match languages.len() {
+            1 => languages.into_iter().next(),
+            _ => None,
+        }
+    };
+
+    antenna_query
+        .forced_language()?
+        .or_else(|| {
+            test.path
+                .as_ref()
+                .and_then(|x| language_map.detect(x, fixture.code.as_bytes()))
+        })
+        .or_else(only_variant)
+        .or_else(only_included)
+        .ok_or(AntennaError::Antenna {
+            message: String::from(
+                "Could not determine the language of the test, set its `language`",
+            ),
+        })
+}
+
+/// Describes the missing and unexpected captures of an [`Outcome`], one per line, with one-based
+/// lines and columns.
+fn describe_outcome(outcome: &Outcome) -> String {
+    let missing = outcome.missing.iter().map(|x| {
+        format!(
+            "missing `{}` at {}:{}-{}",
+            x.name,
+            x.line + 1,
+            x.start_column + 1,
+            x.end_column + 1
+        )
+    });
+
+    let unexpected = outcome.unexpected.iter().map(|x| {
+        format!(
+            "unexpected `{}` at {}:{}-{}:{} `{}`",
+            x.name,
+            x.start_line + 1,
+            x.start_column + 1,
+            x.end_line + 1,
+            x.end_column + 1,
+            x.text
+        )
+    });
+
+    missing.chain(unexpected).collect::<Vec<_>>().join("\n")
+}
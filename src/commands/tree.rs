@@ -0,0 +1,151 @@
+use std::{fs, io::prelude::*};
+
+use antenna::{
+    configuration::AntennaConfiguration, detect::LanguageMap, process::index::IndexedFile,
+    AntennaError, AntennaResult, RecognizedLanguage,
+};
+use tree_sitter::TreeCursor;
+
+use crate::args::{TreeArguments, TreeFormat};
+
+/// Options determining which nodes are printed and how.
+struct TreeOptions<'a> {
+    format: TreeFormat,
+    range: Option<(usize, usize)>,
+    anonymous: bool,
+    content: &'a [u8],
+}
+
+/// Parses the file given in the [`TreeArguments`] and prints its syntax tree, with the field name,
+/// point range and byte range of every node. Points are zero-based rows and byte columns, as in
+/// query output.
+///
+/// The language is detected via the `languages` and `grammars` of the given
+/// [`configuration`](AntennaConfiguration), if any, unless a language is given explicitly.
+pub fn execute(
+    configuration: Option<AntennaConfiguration>,
+    arguments: TreeArguments,
+) -> AntennaResult<()> {
+    let language_map = match &configuration {
+        Some(configuration) => {
+            antenna::grammar::load_grammars(configuration)?;
+            LanguageMap::from_configuration(configuration)?
+        },
+
+        None => LanguageMap::default(),
+    };
+
+    let content = fs::read(&arguments.path)?;
+
+    let language = match &arguments.language {
+        Some(name) => {
+            RecognizedLanguage::from_name(name).ok_or(AntennaError::Antenna {
+                message: format!("`{name}` is not a recognized language"),
+            })?
+        },
+
+        None => {
+            language_map
+                .detect(&arguments.path, &content)
+                .ok_or(AntennaError::Antenna {
+                    message: format!(
+                        "Could not detect the language of {:?}, pass `--language`",
+                        arguments.path
+                    ),
+                })?
+        },
+    };
+
+    let file = IndexedFile::parse(&arguments.path, content, language, None)?;
+
+    let options = TreeOptions {
+        format: arguments.format,
+        range: arguments.range,
+        anonymous: arguments.anonymous,
+        content: &file.content,
+    };
+
+    let mut output = String::new();
+    write_node(&mut output, &mut file.tree.walk(), &options, 0);
+
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{}", output.trim_start_matches('\n'))?;
+
+    Ok(())
+}
+
+/// Writes the node the `cursor` points at and its descendants to the `output`, indented by
+/// `depth` levels, skipping anonymous nodes unless requested and nodes outside of the requested
+/// range along with their descendants.
+fn write_node(
+    output: &mut String,
+    cursor: &mut TreeCursor,
+    options: &TreeOptions,
+    depth: usize,
+) {
+    let node = cursor.node();
+
+    let is_in_range = options.range.is_none_or(|(start, end)| {
+        node.start_position().row <= end && node.end_position().row >= start
+    });
+
+    if !(node.is_named() || options.anonymous) || !is_in_range {
+        return;
+    }
+
+    let indentation = "  ".repeat(depth);
+    let field = cursor
+        .field_name()
+        .map(|x| format!("{x}: "))
+        .unwrap_or_default();
+
+    let kind = match (node.is_named(), node.is_missing()) {
+        (true, false) => node.kind().to_owned(),
+        (true, true) => format!("MISSING {}", node.kind()),
+        (false, false) => format!("{:?}", node.kind()),
+        (false, true) => format!("MISSING {:?}", node.kind()),
+    };
+
+    let (start, end) = (node.start_position(), node.end_position());
+    let range = format!(
+        "[{}:{} - {}:{}] {}..{}",
+        start.row,
+        start.column,
+        end.row,
+        end.column,
+        node.start_byte(),
+        node.end_byte()
+    );
+
+    let line = match options.format {
+        TreeFormat::Sexp => format!("\n{indentation}{field}({kind} {range}"),
+        TreeFormat::Outline => {
+            match node.child_count() == 0 && node.is_named() {
+                true => {
+                    let text = String::from_utf8_lossy(&options.content[node.byte_range()]);
+                    format!("\n{indentation}{field}{kind} {range} {text:?}")
+                },
+
+                false => format!("\n{indentation}{field}{kind} {range}"),
+            }
+        },
+    };
+
+    output.push_str(&line);
+
+    if cursor.goto_first_child() {
+        loop {
+            write_node(output, cursor, options, depth + 1);
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        cursor.goto_parent();
+    }
+
+    if options.format == TreeFormat::Sexp {
+        output.push(')');
+    }
+}
@@ -0,0 +1,188 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+
+use antenna::{
+    baseline::Baseline,
+    configuration::{AntennaConfiguration, AntennaSeverity},
+    out,
+    process::{index::Indexer, query::QueryCache},
+    AntennaResult,
+};
+use notify::{EventKind, RecursiveMode, Watcher};
+use rayon::prelude::*;
+
+/// Time to wait for further file system events after an event, so that bursts of events, such as
+/// those of an editor saving a file, are handled at once.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Executes every query in the given [`configuration`](AntennaConfiguration), then watches the
+/// current directory for changes until interrupted.
+///
+/// Changed files are re-indexed incrementally via [`Indexer::update`], after which only the
+/// queries whose files changed are executed again and have their outputs rewritten. Errors
+/// encountered while watching are printed rather than ending the watch.
+///
+/// If a [`Baseline`] is given, matches that are part of it are neither written nor counted.
+pub fn execute(
+    configuration: AntennaConfiguration,
+    fail_on: Option<AntennaSeverity>,
+    baseline: Option<Baseline>,
+) -> AntennaResult<()> {
+    let (query_cache, mut indexer) = super::prepare(&configuration, Indexer::default())?;
+
+    // Diagnostics of the initial index were already printed while preparing it.
+    indexer.take_diagnostics();
+    let all_queries = configuration
+        .queries
+        .iter()
+        .map(|x| x.name.clone())
+        .collect();
+
+    let mut results = vec![Vec::new(); configuration.queries.len()];
+    rerun(
+        &configuration,
+        &indexer,
+        &query_cache,
+        baseline.as_ref(),
+        &mut results,
+        &all_queries,
+    )?;
+    report(&configuration, &results, fail_on, &all_queries)?;
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    let current_directory = std::env::current_dir()?;
+
+    watcher.watch(Path::new("."), RecursiveMode::Recursive)?;
+    eprintln!("Watching for changes, press Ctrl-C to stop");
+
+    while let Ok(event) = receiver.recv() {
+        let mut events = vec![event];
+
+        while let Ok(event) = receiver.recv_timeout(DEBOUNCE) {
+            events.push(event);
+        }
+
+        let mut paths = HashSet::new();
+
+        for event in events {
+            match event {
+                Ok(event) => {
+                    if matches!(
+                        event.kind,
+                        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                    ) {
+                        paths.extend(
+                            event
+                                .paths
+                                .iter()
+                                .filter_map(|x| relative_path(&current_directory, x)),
+                        );
+                    }
+                },
+
+                Err(err) => eprintln!("error: {}", super::describe(&err.into())),
+            }
+        }
+
+        if paths.is_empty() {
+            continue;
+        }
+
+        let updated = indexer.update(&configuration, paths);
+
+        for diagnostic in indexer.take_diagnostics() {
+            eprintln!("warning: {diagnostic}");
+        }
+
+        let affected = match updated {
+            Ok(affected) => affected,
+            Err(err) => {
+                eprintln!("error: {}", super::describe(&err));
+                continue;
+            },
+        };
+
+        if affected.is_empty() {
+            continue;
+        }
+
+        let rerun = rerun(
+            &configuration,
+            &indexer,
+            &query_cache,
+            baseline.as_ref(),
+            &mut results,
+            &affected,
+        )
+        .and_then(|_| report(&configuration, &results, fail_on, &affected));
+
+        if let Err(err) = rerun {
+            eprintln!("error: {}", super::describe(&err));
+        }
+    }
+
+    Ok(())
+}
+
+/// Executes the queries of the given [`configuration`](AntennaConfiguration) whose names are
+/// `affected` again, replacing their entries in `results` with the matches that are not part of
+/// the given [`Baseline`].
+fn rerun(
+    configuration: &AntennaConfiguration,
+    indexer: &Indexer,
+    query_cache: &QueryCache,
+    baseline: Option<&Baseline>,
+    results: &mut [Vec<out::Query<'static>>],
+    affected: &HashSet<String>,
+) -> AntennaResult<()> {
+    configuration
+        .queries
+        .par_iter()
+        .zip(results)
+        .filter(|(antenna_query, _)| affected.contains(&antenna_query.name))
+        .try_for_each(|(antenna_query, out_queries)| {
+            let mut executed =
+                antenna::process::execute_antenna_query(antenna_query, indexer, query_cache)?;
+
+            if let Some(baseline) = baseline {
+                baseline.retain_new(&mut executed);
+            }
+
+            *out_queries = executed.into_iter().map(out::Query::into_owned).collect();
+            Ok(())
+        })
+}
+
+/// Writes the outputs of the `affected` queries and prints their failures.
+fn report(
+    configuration: &AntennaConfiguration,
+    results: &[Vec<out::Query>],
+    fail_on: Option<AntennaSeverity>,
+    affected: &HashSet<String>,
+) -> AntennaResult<()> {
+    let failures = super::run::report(configuration, results, fail_on, |x| {
+        affected.contains(&x.name)
+    })?;
+
+    for failure in &failures {
+        eprintln!("error: {failure}");
+    }
+
+    Ok(())
+}
+
+/// Converts a `path` reported by the watcher into a path relative to the `current_directory`, in
+/// the form paths are indexed in. Paths within `.git` directories are omitted.
+fn relative_path(current_directory: &Path, path: &Path) -> Option<PathBuf> {
+    let relative = super::relative_path(current_directory, path);
+
+    match relative.components().any(|x| x.as_os_str() == ".git") {
+        true => None,
+        false => Some(relative),
+    }
+}
@@ -1,18 +1,227 @@
-use std::collections::HashSet;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    path::PathBuf,
+};
 
-use serde::{Deserialize, Serialize};
+use clap::ValueEnum;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{out::sarif, AntennaError, AntennaResult, RecognizedLanguage};
 
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct AntennaConfiguration {
+    /// Determines how errors encountered while indexing files are handled.
+    #[serde(default)]
+    pub error_policy: AntennaErrorPolicy,
+
+    /// Maps file extensions or glob patterns to the [name](RecognizedLanguage::name) of the
+    /// language files matching them are parsed as, taking precedence over built-in detection.
+    #[serde(default)]
+    pub languages: BTreeMap<String, String>,
+
+    /// Tree Sitter grammars loaded from shared libraries at runtime.
+    #[serde(default)]
+    pub grammars: Vec<AntennaGrammar>,
+
     pub queries: Vec<AntennaQuery>,
 }
 
+/// A Tree Sitter grammar compiled to a shared library, registered under `name` once loaded.
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct AntennaGrammar {
+    pub name: String,
+    pub path: PathBuf,
+
+    /// Name of the function returning the grammar's language, defaults to `tree_sitter_{name}`.
+    pub symbol: Option<String>,
+}
+
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct AntennaQuery {
     pub name: String,
-    pub include: String,
-    pub query: String,
+
+    /// Glob patterns of files the query should be executed against, may be a single pattern or a
+    /// list of patterns.
+    #[serde(deserialize_with = "deserialize_one_or_many")]
+    pub include: Vec<String>,
+
+    /// Glob patterns of files that should be excluded from the files matched by `include`.
+    #[serde(default, deserialize_with = "deserialize_one_or_many")]
+    pub exclude: Vec<String>,
+
+    /// Determines if `.gitignore`, `.ignore`, `.git/info/exclude` and global git excludes
+    /// should be respected when discovering files, defaults to `true`.
+    #[serde(default = "default_respect_ignore")]
+    pub respect_ignore: bool,
+
+    /// [Name](RecognizedLanguage::name) of the language every included file is parsed as,
+    /// regardless of its detected language.
+    pub language: Option<String>,
+
+    pub query: AntennaQueryText,
+
+    /// Severity of the query's matches, defaults to [`AntennaSeverity::Warning`].
+    #[serde(default)]
+    pub severity: AntennaSeverity,
+
+    /// Message reported for each match, `{capture}` placeholders are replaced with the text of
+    /// the match's capture of the same name.
+    pub message: Option<String>,
+
+    /// Replacement for the source spanning all captures of each match, applied via `antenna fix`.
+    /// `@capture` references are replaced with the text of the match's capture of the same name.
+    pub fix: Option<String>,
+
+    /// Maximum number of matches allowed before the query is considered failing.
+    pub max_matches: Option<usize>,
+
     pub output: Option<HashSet<AntennaOutputMode>>,
+
+    /// Annotated code snippets the query is checked against via `antenna test`.
+    #[serde(default)]
+    pub tests: Vec<AntennaQueryTest>,
+}
+
+/// The Tree Sitter query text of an [`AntennaQuery`], either shared by every language or keyed
+/// by the [name](RecognizedLanguage::name) of the language the variant applies to.
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum AntennaQueryText {
+    Shared(String),
+    PerLanguage(BTreeMap<String, String>),
+}
+
+/// A test of an [`AntennaQuery`], code annotated with the captures the query is expected to
+/// produce, see [`Fixture`](crate::fixture::Fixture).
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct AntennaQueryTest {
+    /// Name of the test, defaults to its `path` or its position within the query's tests.
+    pub name: Option<String>,
+
+    /// [Name](RecognizedLanguage::name) of the language the code is parsed as, defaults to the
+    /// query's `language`, the language detected from `path`, the query's only variant or the
+    /// only language its `include` patterns refer to.
+    pub language: Option<String>,
+
+    /// Annotated code, mutually exclusive with `path`. The code is parsed at a path matching the
+    /// query's first `include` pattern.
+    pub code: Option<String>,
+
+    /// Path to a fixture file containing annotated code, mutually exclusive with `code`.
+    pub path: Option<PathBuf>,
+}
+
+impl AntennaQuery {
+    /// Resolves the `language` the [`AntennaQuery`] forces its files to be parsed as, returning
+    /// an error if the language is not recognized.
+    pub fn forced_language(&self) -> AntennaResult<Option<RecognizedLanguage>> {
+        let Some(name) = &self.language else {
+            return Ok(None);
+        };
+
+        match RecognizedLanguage::from_name(name) {
+            Some(language) => Ok(Some(language)),
+            None => {
+                Err(AntennaError::Antenna {
+                    message: format!(
+                        "Query `{}` forces the unrecognized language `{name}`",
+                        self.name
+                    ),
+                })
+            },
+        }
+    }
+}
+
+impl AntennaQueryText {
+    /// Retrieves the query text applicable to the given `language`, returning [`None`] if there
+    /// is no variant for the language.
+    pub fn for_language(&self, language: RecognizedLanguage) -> Option<&str> {
+        match self {
+            Self::Shared(text) => Some(text),
+            Self::PerLanguage(variants) => {
+                variants
+                    .iter()
+                    .find(|(name, _)| RecognizedLanguage::from_name(name) == Some(language))
+                    .map(|(_, text)| text.as_str())
+            },
+        }
+    }
+
+    /// Retrieves the query text applicable to code of the given `language` injected into another
+    /// file, returning [`None`] if there is no variant for the language.
+    ///
+    /// Unlike [`for_language`](Self::for_language), shared query text is never applied to
+    /// injected code, as it is written against the grammar of the files it includes.
+    pub fn for_injected_language(&self, language: RecognizedLanguage) -> Option<&str> {
+        match self {
+            Self::Shared(_) => None,
+            Self::PerLanguage(_) => self.for_language(language),
+        }
+    }
+}
+
+/// Severity of the matches of an [`AntennaQuery`], ordered from least to most severe.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Deserialize,
+    Serialize,
+    ValueEnum,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AntennaSeverity {
+    Info,
+
+    #[default]
+    Warning,
+
+    Error,
+}
+
+impl fmt::Display for AntennaSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Info => write!(f, "info"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl From<AntennaSeverity> for sarif::Level {
+    fn from(severity: AntennaSeverity) -> Self {
+        match severity {
+            AntennaSeverity::Info => Self::Note,
+            AntennaSeverity::Warning => Self::Warning,
+            AntennaSeverity::Error => Self::Error,
+        }
+    }
+}
+
+/// Determines how errors encountered while indexing files are handled.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, ValueEnum,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AntennaErrorPolicy {
+    /// Abort on the first error encountered.
+    Fail,
+
+    /// Skip the offending files, reporting each error as a warning.
+    #[default]
+    Warn,
+
+    /// Skip the offending files without reporting the errors.
+    Ignore,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
@@ -26,7 +235,48 @@ pub enum AntennaOutputMode {
 
     Csv {
         path: String,
+
+        /// Appends rows to the file instead of replacing it, the header row is only written if
+        /// the file is empty.
+        #[serde(default)]
+        append: bool,
+    },
+
+    /// Writes matches as a SARIF 2.1.0 log, queries sharing the same `path` are written to the
+    /// same log as separate rules.
+    Sarif {
+        path: String,
+
+        /// Identifier of the rule the query is reported as, defaults to the query's name.
+        rule_id: Option<String>,
+
+        /// Message describing the rule, defaults to the query's message.
+        message: Option<String>,
+
+        /// Level of the rule the query is reported as, defaults to the query's severity.
+        level: Option<sarif::Level>,
     },
 
     Occurrences,
 }
+
+fn default_respect_ignore() -> bool { true }
+
+/// Deserializes either a single value or a sequence of values into a [`Vec`].
+fn deserialize_one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => Ok(vec![value]),
+        OneOrMany::Many(values) => Ok(values),
+    }
+}
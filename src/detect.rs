@@ -0,0 +1,376 @@
+use std::path::Path;
+
+use glob::Pattern;
+
+use crate::{
+    configuration::AntennaConfiguration, process::discover::MATCH_OPTIONS, AntennaError,
+    AntennaResult, RecognizedLanguage,
+};
+
+/// Number of lines at the start and end of a file searched for modelines.
+const MODELINE_SEARCH_LINES: usize = 5;
+
+/// User configured mappings of file extensions and glob patterns to [`RecognizedLanguages`](RecognizedLanguage),
+/// which take precedence over the built-in detection of [`RecognizedLanguage::detect`].
+#[derive(Debug, Default, Clone)]
+pub struct LanguageMap {
+    /// Extensions without a leading `.`, sorted from longest to shortest so that `yml.tmpl` is
+    /// preferred over `tmpl`.
+    extensions: Vec<(String, RecognizedLanguage)>,
+
+    patterns: Vec<(Pattern, RecognizedLanguage)>,
+}
+
+impl LanguageMap {
+    /// Constructs a [`LanguageMap`] from the `languages` section of the given
+    /// [`configuration`](AntennaConfiguration).
+    ///
+    /// Keys containing a wildcard or path separator are treated as glob patterns, patterns
+    /// without a path separator are matched against file names while others are matched against
+    /// entire paths. All other keys are treated as file extensions, which may contain multiple
+    /// components such as `yml.tmpl`.
+    pub fn from_configuration(configuration: &AntennaConfiguration) -> AntennaResult<Self> {
+        let mut language_map = Self::default();
+
+        for (key, name) in &configuration.languages {
+            let language =
+                RecognizedLanguage::from_name(name).ok_or(AntennaError::Antenna {
+                    message: format!("`{key}` is mapped to the unrecognized language `{name}`"),
+                })?;
+
+            match key.contains(['*', '?', '[', '/']) {
+                true => {
+                    let pattern = key.strip_prefix("./").unwrap_or(key);
+                    language_map
+                        .patterns
+                        .push((Pattern::new(pattern)?, language));
+                },
+
+                false => {
+                    let extension = key.trim_start_matches('.').to_lowercase();
+                    language_map.extensions.push((extension, language));
+                },
+            }
+        }
+
+        language_map
+            .extensions
+            .sort_by_key(|(extension, _)| std::cmp::Reverse(extension.len()));
+
+        Ok(language_map)
+    }
+
+    /// Attempts to resolve the [`RecognizedLanguage`] of the file at `path` using only the
+    /// configured mappings, returning [`None`] if no mapping applies.
+    pub fn resolve<P>(&self, path: P) -> Option<RecognizedLanguage>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let path = path.strip_prefix(".").unwrap_or(path);
+        let file_name = path.file_name()?.to_str()?;
+
+        let by_pattern = self.patterns.iter().find(|(pattern, _)| {
+            match pattern.as_str().contains('/') {
+                true => pattern.matches_path_with(path, MATCH_OPTIONS),
+                false => pattern.matches_with(file_name, MATCH_OPTIONS),
+            }
+        });
+
+        if let Some((_, language)) = by_pattern {
+            return Some(*language);
+        }
+
+        let file_name = file_name.to_lowercase();
+
+        self.extensions
+            .iter()
+            .find(|(extension, _)| {
+                file_name
+                    .strip_suffix(extension.as_str())
+                    .is_some_and(|x| x.ends_with('.') && x.len() > 1)
+            })
+            .map(|(_, language)| *language)
+    }
+
+    /// Detects the [`RecognizedLanguage`] of the file at `path` with the given `content`,
+    /// preferring the configured mappings over [`RecognizedLanguage::detect`].
+    pub fn detect<P>(&self, path: P, content: &[u8]) -> Option<RecognizedLanguage>
+    where
+        P: AsRef<Path>,
+    {
+        self.resolve(&path)
+            .or_else(|| RecognizedLanguage::detect(&path, content))
+    }
+}
+
+impl RecognizedLanguage {
+    /// Attempts to detect the [`RecognizedLanguage`] of the file at `path` with the given
+    /// `content`. Detection considers, in order, editor modelines, exact file names, shebang
+    /// lines and file extensions, returning the first language found.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use antenna::RecognizedLanguage;
+    ///
+    /// assert_eq!(
+    ///     RecognizedLanguage::detect("bin/deploy", b"#!/usr/bin/env python3\n"),
+    ///     Some(RecognizedLanguage::Python)
+    /// );
+    /// ```
+    pub fn detect<P>(path: P, content: &[u8]) -> Option<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let first_line = content.split(|&x| x == b'\n').next().unwrap_or_default();
+
+        Self::from_modeline(content)
+            .or_else(|| Self::from_file_name(path.file_name()?.to_str()?))
+            .or_else(|| Self::from_shebang(&String::from_utf8_lossy(first_line)))
+            .or_else(|| Self::from_language_extension(path.extension()?.to_str()?))
+    }
+
+    /// Attempts to construct a [`RecognizedLanguage`] from a file's exact name, for files that
+    /// are conventionally named without a meaningful extension.
+    /// If the name is not mappable to a _[`RecognizedLanguage`]_, this function will return [`None`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use antenna::RecognizedLanguage;
+    ///
+    /// assert_eq!(
+    ///     RecognizedLanguage::from_file_name("Rakefile"),
+    ///     Some(RecognizedLanguage::Ruby)
+    /// );
+    /// ```
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        match name {
+            #[cfg(feature = "python")]
+            "SConstruct" | "SConscript" | "wscript" => Some(Self::Python),
+
+            #[cfg(feature = "ruby")]
+            "Rakefile" | "Gemfile" | "Guardfile" | "Podfile" | "Vagrantfile" | "Capfile"
+            | "Brewfile" | "Fastfile" | "Dangerfile" | ".irbrc" | ".pryrc" => Some(Self::Ruby),
+
+            #[cfg(feature = "json")]
+            ".babelrc" | ".eslintrc" | ".prettierrc" | ".jshintrc" | ".swcrc"
+            | ".watchmanconfig" => Some(Self::Json),
+
+            #[cfg(feature = "toml")]
+            "Pipfile" | "Cargo.lock" | "poetry.lock" => Some(Self::Toml),
+
+            #[cfg(feature = "yaml")]
+            ".clang-format" | ".clang-tidy" | ".clangd" => Some(Self::Yaml),
+
+            #[cfg(feature = "bash")]
+            ".bashrc" | ".bash_profile" | ".bash_logout" | ".profile" | "PKGBUILD" => {
+                Some(Self::Bash)
+            },
+
+            #[cfg(feature = "elixir")]
+            "mix.lock" => Some(Self::Elixir),
+
+            #[cfg(feature = "dockerfile")]
+            "Dockerfile" | "Containerfile" => Some(Self::Dockerfile),
+
+            #[cfg(feature = "dockerfile")]
+            name if name.starts_with("Dockerfile.") => Some(Self::Dockerfile),
+
+            _ => None,
+        }
+    }
+
+    /// Attempts to construct a [`RecognizedLanguage`] from a shebang line, such as
+    /// `#!/usr/bin/env python3`.
+    /// If the line is not a shebang, or its interpreter is not mappable to a
+    /// _[`RecognizedLanguage`]_, this function will return [`None`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use antenna::RecognizedLanguage;
+    ///
+    /// assert_eq!(
+    ///     RecognizedLanguage::from_shebang("#!/usr/bin/env -S ruby -w"),
+    ///     Some(RecognizedLanguage::Ruby)
+    /// );
+    /// ```
+    pub fn from_shebang(line: &str) -> Option<Self> {
+        let mut arguments = line.strip_prefix("#!")?.split_whitespace();
+        let mut interpreter = Self::strip_directories(arguments.next()?);
+
+        if interpreter == "env" {
+            interpreter = arguments.find(|x| !x.starts_with('-') && !x.contains('='))?;
+        }
+
+        Self::from_interpreter(Self::strip_directories(interpreter))
+    }
+
+    /// Attempts to construct a [`RecognizedLanguage`] from the name of an interpreter, ignoring
+    /// any trailing version, e.g. `python3.11`.
+    /// If the interpreter is not mappable to a _[`RecognizedLanguage`]_, this function will
+    /// return [`None`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use antenna::RecognizedLanguage;
+    ///
+    /// assert_eq!(
+    ///     RecognizedLanguage::from_interpreter("node"),
+    ///     Some(RecognizedLanguage::JavaScript)
+    /// );
+    /// ```
+    pub fn from_interpreter(interpreter: &str) -> Option<Self> {
+        match interpreter.trim_end_matches(|x: char| x.is_ascii_digit() || x == '.') {
+            #[cfg(feature = "python")]
+            "python" | "pypy" => Some(Self::Python),
+
+            #[cfg(feature = "ruby")]
+            "ruby" | "jruby" | "rake" => Some(Self::Ruby),
+
+            #[cfg(feature = "javascript")]
+            "node" | "nodejs" => Some(Self::JavaScript),
+
+            #[cfg(feature = "typescript")]
+            "ts-node" | "deno" | "bun" => Some(Self::TypeScript),
+
+            #[cfg(feature = "bash")]
+            "sh" | "bash" | "dash" => Some(Self::Bash),
+
+            #[cfg(feature = "php")]
+            "php" => Some(Self::Php),
+
+            #[cfg(feature = "lua")]
+            "lua" | "luajit" => Some(Self::Lua),
+
+            #[cfg(feature = "elixir")]
+            "elixir" => Some(Self::Elixir),
+
+            #[cfg(feature = "haskell")]
+            "runghc" | "runhaskell" => Some(Self::Haskell),
+
+            #[cfg(feature = "scala")]
+            "scala" => Some(Self::Scala),
+
+            _ => None,
+        }
+    }
+
+    /// Attempts to construct a [`RecognizedLanguage`] from a Vim or Emacs modeline found within
+    /// the first or last lines of a file's `content`, such as `vim: set ft=python:` or
+    /// `-*- mode: ruby -*-`.
+    /// If no modeline is found, or its language is not mappable to a _[`RecognizedLanguage`]_,
+    /// this function will return [`None`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use antenna::RecognizedLanguage;
+    ///
+    /// assert_eq!(
+    ///     RecognizedLanguage::from_modeline(b"# -*- mode: python -*-\nprint()\n"),
+    ///     Some(RecognizedLanguage::Python)
+    /// );
+    ///
+    /// assert_eq!(
+    ///     RecognizedLanguage::from_modeline(b"regex: ft=python\n"),
+    ///     None
+    /// );
+    /// ```
+    pub fn from_modeline(content: &[u8]) -> Option<Self> {
+        let content = content.strip_suffix(b"\n").unwrap_or(content);
+
+        let head = content
+            .splitn(MODELINE_SEARCH_LINES + 1, |&x| x == b'\n')
+            .take(MODELINE_SEARCH_LINES)
+            .collect::<Vec<_>>();
+
+        let head_length = head.iter().map(|x| x.len() + 1).sum::<usize>();
+
+        let mut tail = content
+            .get(head_length..)
+            .unwrap_or_default()
+            .rsplit(|&x| x == b'\n')
+            .take(MODELINE_SEARCH_LINES)
+            .collect::<Vec<_>>();
+
+        tail.reverse();
+
+        head.into_iter().chain(tail).find_map(|x| {
+            let line = String::from_utf8_lossy(x);
+
+            Self::parse_vim_modeline(&line)
+                .or_else(|| Self::parse_emacs_modeline(&line))
+                .and_then(Self::from_mode_name)
+        })
+    }
+
+    /// Parses the file type of a Vim modeline, e.g. `vim: set ft=python:` or `vi: filetype=ruby`.
+    /// The marker has to start the line or follow whitespace, so `regex:` is not a modeline.
+    fn parse_vim_modeline(line: &str) -> Option<&str> {
+        let start = ["vim:", "vi:", "ex:"].iter().find_map(|marker| {
+            line.match_indices(marker)
+                .find(|(i, _)| {
+                    line[..*i]
+                        .chars()
+                        .next_back()
+                        .is_none_or(char::is_whitespace)
+                })
+                .map(|(i, _)| i + marker.len())
+        })?;
+
+        line[start..]
+            .split(|x: char| x.is_whitespace() || x == ':')
+            .find_map(|x| {
+                x.strip_prefix("ft=")
+                    .or_else(|| x.strip_prefix("filetype="))
+            })
+    }
+
+    /// Parses the major mode of an Emacs modeline, e.g. `-*- mode: ruby -*-` or `-*- ruby -*-`.
+    fn parse_emacs_modeline(line: &str) -> Option<&str> {
+        let start = line.find("-*-")? + 3;
+        let end = start + line[start..].find("-*-")?;
+        let variables = line[start..end].trim();
+
+        if !variables.contains(':') {
+            return Some(variables);
+        }
+
+        variables.split(';').find_map(|x| {
+            let (key, value) = x.split_once(':')?;
+            (key.trim().eq_ignore_ascii_case("mode")).then(|| value.trim())
+        })
+    }
+
+    /// Maps the name of a Vim file type or Emacs major mode to a [`RecognizedLanguage`].
+    fn from_mode_name(mode: &str) -> Option<Self> {
+        let mode = mode.to_lowercase();
+        let mode = mode.strip_suffix("-mode").unwrap_or(&mode);
+
+        match mode {
+            #[cfg(feature = "cpp")]
+            "c++" => Some(Self::Cpp),
+
+            #[cfg(feature = "c-sharp")]
+            "csharp" => Some(Self::CSharp),
+
+            #[cfg(feature = "javascript")]
+            "js" => Some(Self::JavaScript),
+
+            #[cfg(feature = "bash")]
+            "sh" | "shell-script" => Some(Self::Bash),
+
+            _ => Self::from_name(mode).or_else(|| Self::from_language_extension(mode)),
+        }
+    }
+
+    /// Strips any leading directories from an interpreter's path.
+    fn strip_directories(interpreter: &str) -> &str {
+        interpreter.rsplit('/').next().unwrap_or(interpreter)
+    }
+}
@@ -0,0 +1,54 @@
+use std::{error::Error, fmt, path::PathBuf};
+
+use crate::AntennaError;
+
+/// A non-fatal error encountered while processing the files of an
+/// [`AntennaQuery`](crate::configuration::AntennaQuery).
+#[derive(Debug)]
+pub struct Diagnostic {
+    /// Name of the query the error was encountered for.
+    pub query: String,
+
+    /// Path of the file the error was encountered for, [`None`] if the error is not specific to
+    /// a single file.
+    pub path: Option<PathBuf>,
+
+    pub error: AntennaError,
+}
+
+impl Diagnostic {
+    /// Converts the [`Diagnostic`] into an [`AntennaError`], retaining the path of the file the
+    /// error was encountered for.
+    pub fn into_error(self) -> AntennaError {
+        match self.path {
+            Some(path) => {
+                AntennaError::File {
+                    path,
+                    inner: Box::new(self.error),
+                }
+            },
+
+            None => self.error,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "`{}` {}: ", self.query, path.display())?,
+            None => write!(f, "`{}`: ", self.query)?,
+        }
+
+        write!(f, "{}", self.error)?;
+
+        let mut source = self.error.source();
+
+        while let Some(error) = source {
+            write!(f, ": {error}")?;
+            source = error.source();
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,336 @@
+use std::{ops::Range, path::Path};
+
+use crate::{configuration::AntennaQuery, out};
+
+/// Number of unchanged lines surrounding each change in a unified diff.
+const CONTEXT: usize = 3;
+
+/// A replacement of a range of bytes in a file, produced by the `fix` of a query.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Edit {
+    pub query: String,
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+/// An [`Edit`] that was skipped, as it overlaps an edit that was applied.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Conflict {
+    pub skipped: Edit,
+    pub applied: Edit,
+}
+
+/// The content of a file after applying a set of [edits](Edit).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixed {
+    pub content: String,
+    pub applied: Vec<Edit>,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Edit {
+    /// Creates an [`Edit`] for each match of the given [`out::Query`] from the `fix` of the
+    /// given [`AntennaQuery`], replacing the source spanning all captures of the match in the
+    /// given `content` of its file.
+    ///
+    /// Returns no edits if the query has no `fix`.
+    pub fn from_out_query(
+        antenna_query: &AntennaQuery,
+        out_query: &out::Query,
+        content: &str,
+    ) -> Vec<Self> {
+        let Some(template) = &antenna_query.fix else {
+            return Vec::new();
+        };
+
+        let line_starts = line_starts(content);
+        let offset = |(line, column): (usize, usize)| {
+            line_starts.get(line).map_or(content.len(), |x| x + column)
+        };
+
+        out_query
+            .matches
+            .iter()
+            .filter_map(|out_match| {
+                let start = out_match
+                    .captures
+                    .iter()
+                    .map(|x| (x.start_line, x.start_column))
+                    .min()?;
+
+                let end = out_match
+                    .captures
+                    .iter()
+                    .map(|x| (x.end_line, x.end_column))
+                    .max()?;
+
+                Some(Self {
+                    query: antenna_query.name.clone(),
+                    range: offset(start)..offset(end),
+                    replacement: out_match.render_fix(template),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Applies the given `edits` to the `content` of a file.
+///
+/// Edits are applied in the order of their ranges. An edit overlapping an edit that was already
+/// applied is skipped and reported as a [`Conflict`], unless both are identical, in which case
+/// it is applied once.
+///
+/// # Example
+///
+/// ```rust
+/// use antenna::fix::{apply, Edit};
+///
+/// let edit = |range, replacement: &str| {
+///     Edit {
+///         query: String::from("example"),
+///         range,
+///         replacement: replacement.to_owned(),
+///     }
+/// };
+///
+/// let fixed = apply(
+///     "a.unwrap()",
+///     vec![edit(0..10, "a?"), edit(2..10, "expect()")],
+/// );
+///
+/// assert_eq!(fixed.content, "a?");
+/// assert_eq!(fixed.conflicts.len(), 1);
+/// ```
+pub fn apply(content: &str, mut edits: Vec<Edit>) -> Fixed {
+    edits.sort_by_key(|x| (x.range.start, x.range.end));
+
+    let mut applied = Vec::<Edit>::new();
+    let mut conflicts = Vec::new();
+
+    for edit in edits {
+        match applied.last() {
+            Some(last) if last.range == edit.range && last.replacement == edit.replacement => {
+                continue;
+            },
+
+            Some(last) if edit.range.start < last.range.end => {
+                conflicts.push(Conflict {
+                    skipped: edit,
+                    applied: last.clone(),
+                });
+            },
+
+            _ => applied.push(edit),
+        }
+    }
+
+    let mut fixed = String::with_capacity(content.len());
+    let mut position = 0;
+
+    for edit in &applied {
+        fixed.push_str(&content[position..edit.range.start]);
+        fixed.push_str(&edit.replacement);
+        position = edit.range.end;
+    }
+
+    fixed.push_str(&content[position..]);
+
+    Fixed {
+        content: fixed,
+        applied,
+        conflicts,
+    }
+}
+
+/// Produces a unified diff transforming the `old` content of the file at the given `path` into
+/// the `new` content, or an empty string if both are identical.
+///
+/// # Example
+///
+/// ```rust
+/// use antenna::fix::unified_diff;
+///
+/// assert_eq!(
+///     unified_diff("src/main.rs", "a\nb\nc\n", "a\nB\nc\n"),
+///     "--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"
+/// );
+/// ```
+pub fn unified_diff<P>(path: P, old: &str, new: &str) -> String
+where
+    P: AsRef<Path>,
+{
+    let old_lines = old.split_inclusive('\n').collect::<Vec<_>>();
+    let new_lines = new.split_inclusive('\n').collect::<Vec<_>>();
+    let operations = diff_lines(&old_lines, &new_lines);
+
+    let changes = operations
+        .iter()
+        .enumerate()
+        .filter(|(_, x)| !matches!(x, Operation::Equal(_)))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    let Some(&first) = changes.first() else {
+        return String::new();
+    };
+
+    let path = path.as_ref().display();
+    let mut diff = format!("--- a/{path}\n+++ b/{path}\n");
+
+    let mut hunks = vec![(first, first + 1)];
+
+    for &change in &changes[1..] {
+        let (_, end) = hunks.last_mut().unwrap();
+
+        match change - *end <= 2 * CONTEXT {
+            true => *end = change + 1,
+            false => hunks.push((change, change + 1)),
+        }
+    }
+
+    for (first, last) in hunks {
+        let start = first.saturating_sub(CONTEXT);
+        let end = (last + CONTEXT).min(operations.len());
+
+        let (old_start, new_start) = line_numbers(&operations[..start]);
+        let (old_count, new_count) = line_numbers(&operations[start..end]);
+
+        diff.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        ));
+
+        for operation in &operations[start..end] {
+            let (prefix, line) = match operation {
+                Operation::Equal(line) => (' ', line),
+                Operation::Delete(line) => ('-', line),
+                Operation::Insert(line) => ('+', line),
+            };
+
+            diff.push(prefix);
+            diff.push_str(line);
+
+            if !line.ends_with('\n') {
+                diff.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+    }
+
+    diff
+}
+
+/// An operation of a line based diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+/// Computes the shortest sequence of [operations](Operation) transforming the `old` lines into
+/// the `new` lines, via Myers' algorithm.
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Operation<'a>> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let offset = n + m + 1;
+
+    let mut furthest = vec![0_isize; 2 * offset as usize + 1];
+    let mut trace = Vec::new();
+
+    'search: for d in 0..=n + m {
+        trace.push(furthest[(offset - d) as usize..=(offset + d) as usize].to_vec());
+
+        for k in (-d..=d).step_by(2) {
+            let i = (offset + k) as usize;
+
+            let mut x = match k == -d || (k != d && furthest[i - 1] < furthest[i + 1]) {
+                true => furthest[i + 1],
+                false => furthest[i - 1] + 1,
+            };
+
+            let mut y = x - k;
+
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+
+            furthest[i] = x;
+
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut operations = Vec::new();
+    let (mut x, mut y) = (n, m);
+
+    for (d, furthest) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let at = |k: isize| furthest[(k + d) as usize];
+
+        if d == 0 {
+            while x > 0 && y > 0 {
+                operations.push(Operation::Equal(old[x as usize - 1]));
+                x -= 1;
+                y -= 1;
+            }
+
+            break;
+        }
+
+        let previous_k = match k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            true => k + 1,
+            false => k - 1,
+        };
+
+        let previous_x = at(previous_k);
+        let previous_y = previous_x - previous_k;
+
+        while x > previous_x && y > previous_y {
+            operations.push(Operation::Equal(old[x as usize - 1]));
+            x -= 1;
+            y -= 1;
+        }
+
+        match x == previous_x {
+            true => operations.push(Operation::Insert(new[y as usize - 1])),
+            false => operations.push(Operation::Delete(old[x as usize - 1])),
+        }
+
+        x = previous_x;
+        y = previous_y;
+    }
+
+    operations.reverse();
+    operations
+}
+
+/// Counts the old and new lines spanned by the given `operations`.
+fn line_numbers(operations: &[Operation]) -> (usize, usize) {
+    operations.iter().fold((0, 0), |(old, new), operation| {
+        match operation {
+            Operation::Equal(_) => (old + 1, new + 1),
+            Operation::Delete(_) => (old + 1, new),
+            Operation::Insert(_) => (old, new + 1),
+        }
+    })
+}
+
+/// Formats the range of a hunk starting after `preceding` lines and spanning `count` lines, where
+/// empty ranges refer to the line preceding them.
+fn hunk_range(preceding: usize, count: usize) -> String {
+    match count {
+        0 => format!("{preceding},0"),
+        _ => format!("{},{count}", preceding + 1),
+    }
+}
+
+/// Computes the byte offset each line of the `content` starts at.
+pub fn line_starts(content: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
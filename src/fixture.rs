@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+
+use crate::out;
+
+/// Tokens that may start an annotation line, covering the line comments of recognized languages
+/// as well as block comments opened on the annotation line.
+const COMMENT_LEADERS: [&str; 9] = ["//", "#", "--", ";", "%", "<!--", "/*", "{-", "(*"];
+
+/// Tokens that may close a block comment at the end of an annotation line.
+const COMMENT_CLOSERS: [&str; 4] = ["-->", "*/", "-}", "*)"];
+
+/// A code snippet annotated with the captures a query is expected to produce.
+///
+/// Expected captures are marked via annotation lines, comments consisting of carets spanning the
+/// capture in the closest preceding line of code, followed by the names of the captures:
+///
+/// ```text
+/// fn main() {
+///     value.unwrap();
+/// //  ^^^^^^^^^^^^^^ call
+/// //  ^^^^^ recv
+/// }
+/// ```
+///
+/// Annotation lines are removed from the code before it is parsed, so line numbers refer to the
+/// code without annotations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    pub code: String,
+    pub expected: Vec<Expectation>,
+}
+
+/// A capture expected to start at a zero-based line and byte column, and to end at the given
+/// column if it ends on the same line.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Expectation {
+    pub name: String,
+    pub line: usize,
+    pub start_column: usize,
+    pub end_column: usize,
+}
+
+/// The differences between the expected captures of a [`Fixture`] and the captures produced by a
+/// query.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub missing: Vec<Expectation>,
+    pub unexpected: Vec<out::Capture>,
+}
+
+impl Fixture {
+    /// Parses a [`Fixture`] from annotated `text`, see [`Fixture`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use antenna::fixture::{Expectation, Fixture};
+    ///
+    /// let fixture = Fixture::parse("let a = b.unwrap();\n#       ^^^^^^^^^^ call\n");
+    ///
+    /// assert_eq!(fixture.code, "let a = b.unwrap();\n");
+    /// assert_eq!(
+    ///     fixture.expected,
+    ///     vec![Expectation {
+    ///         name: String::from("call"),
+    ///         line: 0,
+    ///         start_column: 8,
+    ///         end_column: 18,
+    ///     }]
+    /// );
+    /// ```
+    pub fn parse(text: &str) -> Self {
+        let mut code = String::with_capacity(text.len());
+        let mut expected = Vec::new();
+        let mut previous = None::<(usize, &str)>;
+
+        for line in text.split_inclusive('\n') {
+            let annotation = parse_annotation(line);
+
+            let (Some((start, end, names)), Some((code_line, code_text))) =
+                (annotation, previous)
+            else {
+                code.push_str(line);
+                previous = Some((previous.map_or(0, |(x, _)| x + 1), line));
+                continue;
+            };
+
+            let byte_column = |column: usize| {
+                let mut characters = code_text.char_indices().map(|(i, _)| i);
+                characters
+                    .nth(column)
+                    .unwrap_or(code_text.len() + column - code_text.chars().count())
+            };
+
+            for name in names {
+                expected.push(Expectation {
+                    name,
+                    line: code_line,
+                    start_column: byte_column(start),
+                    end_column: byte_column(end),
+                });
+            }
+        }
+
+        Self { code, expected }
+    }
+
+    /// Compares the captures of the given `matches` against the expected captures.
+    ///
+    /// Only captures whose names are annotated somewhere in the fixture are compared, so captures
+    /// used solely by predicates need no annotations. A fixture without any annotations expects
+    /// no captures at all.
+    pub fn check(&self, matches: &[out::Match]) -> Outcome {
+        let annotated = self
+            .expected
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect::<HashSet<_>>();
+
+        let mut seen = HashSet::new();
+        let captures = matches
+            .iter()
+            .flat_map(|x| &x.captures)
+            .filter(|x| self.expected.is_empty() || annotated.contains(x.name.as_str()))
+            .filter(|x| seen.insert(*x))
+            .collect::<Vec<_>>();
+
+        let satisfies = |capture: &out::Capture, expectation: &Expectation| {
+            capture.name == expectation.name
+                && capture.start_line == expectation.line
+                && capture.start_column == expectation.start_column
+                && (capture.end_line != expectation.line
+                    || capture.end_column == expectation.end_column)
+        };
+
+        Outcome {
+            missing: self
+                .expected
+                .iter()
+                .filter(|x| !captures.iter().any(|capture| satisfies(capture, x)))
+                .cloned()
+                .collect(),
+
+            unexpected: captures
+                .into_iter()
+                .filter(|capture| !self.expected.iter().any(|x| satisfies(capture, x)))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl Outcome {
+    /// Determines whether every expected capture was produced, and nothing else.
+    pub fn is_success(&self) -> bool { self.missing.is_empty() && self.unexpected.is_empty() }
+}
+
+/// Parses an annotation `line`, returning the character columns spanned by its carets, with an
+/// exclusive end, and the capture names following them.
+///
+/// Returns [`None`] if the line is not an annotation.
+fn parse_annotation(line: &str) -> Option<(usize, usize, Vec<String>)> {
+    let trimmed = line.trim_start();
+    let remaining = COMMENT_LEADERS
+        .iter()
+        .filter_map(|x| trimmed.strip_prefix(x))
+        .min_by_key(|x| x.len())?;
+
+    let carets = remaining.trim_start();
+
+    if !carets.starts_with('^') {
+        return None;
+    }
+
+    let start = line.chars().count() - carets.chars().count();
+    let end = start + carets.chars().take_while(|&x| x == '^').count();
+
+    let mut names = carets.trim_start_matches('^').trim();
+
+    for closer in COMMENT_CLOSERS {
+        names = names.strip_suffix(closer).unwrap_or(names).trim_end();
+    }
+
+    let names = names
+        .split_whitespace()
+        .map(|x| x.trim_start_matches('@').to_owned())
+        .collect::<Vec<_>>();
+
+    match names.is_empty() {
+        true => None,
+        false => Some((start, end, names)),
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    collections::HashMap,
+    fs,
+    sync::{OnceLock, RwLock},
+};
+
+use libloading::{Library, Symbol};
+use tree_sitter::Language;
+
+use crate::{
+    configuration::AntennaConfiguration, hash::StableHasher, AntennaError, AntennaResult,
+    RecognizedLanguage,
+};
+
+/// Tree Sitter grammars loaded at runtime, keyed by the name they were registered under.
+#[derive(Default)]
+struct Registry {
+    languages: HashMap<&'static str, Language>,
+
+    /// Libraries grammars were loaded from, which must outlive every [`Language`] loaded from
+    /// them and are therefore never unloaded.
+    libraries: Vec<Library>,
+
+    /// Hashes of the libraries grammars were loaded from, keyed by the name of the grammar.
+    fingerprints: HashMap<&'static str, String>,
+}
+
+fn registry() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(RwLock::default)
+}
+
+/// Loads every grammar declared in the `grammars` section of the given
+/// [`configuration`](AntennaConfiguration), registering each under its name.
+pub fn load_grammars(configuration: &AntennaConfiguration) -> AntennaResult<()> {
+    for grammar in &configuration.grammars {
+        let symbol = match &grammar.symbol {
+            Some(symbol) => symbol.to_owned(),
+            None => format!("tree_sitter_{}", grammar.name.replace('-', "_")),
+        };
+
+        let mut hasher = StableHasher::default();
+        hasher.write_field(&fs::read(&grammar.path)?);
+
+        // SAFETY: Loading a library runs its initialization routines, the configuration is
+        // trusted to only declare Tree Sitter grammars.
+        let library = unsafe { Library::new(&grammar.path)? };
+
+        // SAFETY: Tree Sitter grammars expose a function returning a pointer to their
+        // `TSLanguage`, which `Language` is a transparent wrapper of.
+        let language = unsafe {
+            let function: Symbol<unsafe extern "C" fn() -> Language> =
+                library.get(symbol.as_bytes())?;
+
+            function()
+        };
+
+        let name = register(&grammar.name, language, Some(library))?;
+
+        registry()
+            .write()
+            .unwrap()
+            .fingerprints
+            .insert(name, hasher.finish_hex());
+    }
+
+    Ok(())
+}
+
+/// Registers a Tree Sitter [`Language`] under the given `name`, allowing it to be referred to
+/// like a built-in [`RecognizedLanguage`]. Returns the name the language was registered under,
+/// as referred to by [`RecognizedLanguage::Loaded`].
+///
+/// Returns an error if `name` is already used by a built-in or registered language.
+pub fn register(
+    name: &str,
+    language: Language,
+    library: Option<Library>,
+) -> AntennaResult<&'static str> {
+    let already_registered = || {
+        AntennaError::Antenna {
+            message: format!("A language named `{name}` is already registered"),
+        }
+    };
+
+    if RecognizedLanguage::from_name(name).is_some() {
+        return Err(already_registered());
+    }
+
+    let mut registry = registry().write().unwrap();
+
+    if registry.languages.contains_key(name) {
+        return Err(already_registered());
+    }
+
+    let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+
+    registry.languages.insert(name, language);
+    registry.libraries.extend(library);
+
+    Ok(name)
+}
+
+/// Retrieves the [`RecognizedLanguage`] registered under the given `name`.
+pub(crate) fn find(name: &str) -> Option<RecognizedLanguage> {
+    let registry = registry().read().unwrap();
+    registry
+        .languages
+        .get_key_value(name)
+        .map(|(x, _)| RecognizedLanguage::Loaded(x))
+}
+
+/// Retrieves the [`Language`] registered under the given `name`.
+pub(crate) fn language(name: &str) -> Option<Language> {
+    registry().read().unwrap().languages.get(name).copied()
+}
+
+/// Retrieves the hash of the library the grammar registered under the given `name` was loaded
+/// from, if it was loaded via [`load_grammars`].
+pub(crate) fn fingerprint(name: &str) -> Option<String> {
+    registry().read().unwrap().fingerprints.get(name).cloned()
+}
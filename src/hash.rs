@@ -0,0 +1,61 @@
+use std::hash::Hasher;
+
+/// FNV-1a offset basis for 64 bit hashes.
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// FNV-1a prime for 64 bit hashes.
+const PRIME: u64 = 0x0100_0000_01b3;
+
+/// A [`Hasher`] implementing 64 bit FNV-1a, whose output is stable across platforms, processes and
+/// compiler versions, unlike [`DefaultHasher`](std::collections::hash_map::DefaultHasher).
+///
+/// Values should be written via [`Hasher::write`] rather than [`Hash`](std::hash::Hash), as the
+/// latter does not guarantee a stable encoding.
+///
+/// # Example
+///
+/// ```rust
+/// use std::hash::Hasher;
+///
+/// use antenna::hash::StableHasher;
+///
+/// let mut hasher = StableHasher::default();
+/// hasher.write(b"antenna");
+///
+/// assert_eq!(hasher.finish_hex(), format!("{:016x}", hasher.finish()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StableHasher {
+    state: u64,
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self {
+            state: OFFSET_BASIS,
+        }
+    }
+}
+
+impl StableHasher {
+    /// Writes the `bytes` followed by a separator, so that consecutive fields cannot be confused
+    /// with one another.
+    pub fn write_field(&mut self, bytes: &[u8]) {
+        self.write(bytes);
+        self.write_u8(0xFF);
+    }
+
+    /// Retrieves the hash of the values written so far as a lowercase hexadecimal string.
+    pub fn finish_hex(&self) -> String { format!("{:016x}", self.finish()) }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 { self.state }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= u64::from(*byte);
+            self.state = self.state.wrapping_mul(PRIME);
+        }
+    }
+}
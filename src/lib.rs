@@ -1,10 +1,19 @@
-use std::string::FromUtf8Error;
+use std::{path::PathBuf, string::FromUtf8Error};
 
 use glob::{GlobError, PatternError};
 use thiserror::Error;
 use tree_sitter::{Language, LanguageError, QueryError};
 
+pub mod baseline;
+pub mod cache;
+pub mod changes;
 pub mod configuration;
+pub mod detect;
+pub mod diagnostic;
+pub mod fix;
+pub mod fixture;
+pub mod grammar;
+pub mod hash;
 pub mod out;
 pub mod process;
 
@@ -20,6 +29,17 @@ pub enum AntennaError {
         inner: QueryError,
     },
 
+    #[error("failed to compile query `{name}` for `{language:?}` at {}:{}", .row + 1, .column + 1)]
+    QueryCompile {
+        name: String,
+        language: RecognizedLanguage,
+        row: usize,
+        column: usize,
+
+        #[source]
+        inner: QueryError,
+    },
+
     #[error("glob pattern error")]
     Pattern {
         #[from]
@@ -62,15 +82,35 @@ pub enum AntennaError {
         inner: serde_json::Error,
     },
 
+    #[error("library error")]
+    Library {
+        #[from]
+        inner: libloading::Error,
+    },
+
     #[error("csv error")]
     Csv {
         #[from]
         inner: csv::Error,
     },
 
-    #[error("antenna error")]
+    #[error("watch error")]
+    Watch {
+        #[from]
+        inner: notify::Error,
+    },
+
+    #[error("{message}")]
     Antenna { message: String },
 
+    #[error("error in file `{path:?}`")]
+    File {
+        path: PathBuf,
+
+        #[source]
+        inner: Box<AntennaError>,
+    },
+
     #[error("error collection")]
     Collection { errors: Vec<AntennaError> },
 }
@@ -128,6 +168,39 @@ pub enum RecognizedLanguage {
 
     #[cfg(feature = "yaml")]
     Yaml,
+
+    #[cfg(feature = "bash")]
+    Bash,
+
+    #[cfg(feature = "kotlin")]
+    Kotlin,
+
+    #[cfg(feature = "php")]
+    Php,
+
+    #[cfg(feature = "lua")]
+    Lua,
+
+    #[cfg(feature = "scala")]
+    Scala,
+
+    #[cfg(feature = "haskell")]
+    Haskell,
+
+    #[cfg(feature = "elixir")]
+    Elixir,
+
+    #[cfg(feature = "markdown")]
+    Markdown,
+
+    #[cfg(feature = "dockerfile")]
+    Dockerfile,
+
+    #[cfg(feature = "sql")]
+    Sql,
+
+    /// A grammar loaded at runtime, see [`grammar::register`].
+    Loaded(&'static str),
 }
 
 impl RecognizedLanguage {
@@ -195,6 +268,239 @@ impl RecognizedLanguage {
 
             #[cfg(feature = "yaml")]
             Self::Yaml => tree_sitter_yaml::language(),
+
+            #[cfg(feature = "bash")]
+            Self::Bash => tree_sitter_bash::language(),
+
+            #[cfg(feature = "kotlin")]
+            Self::Kotlin => tree_sitter_kotlin::language(),
+
+            #[cfg(feature = "php")]
+            Self::Php => tree_sitter_php::language_php(),
+
+            #[cfg(feature = "lua")]
+            Self::Lua => tree_sitter_lua::language(),
+
+            #[cfg(feature = "scala")]
+            Self::Scala => tree_sitter_scala::language(),
+
+            #[cfg(feature = "haskell")]
+            Self::Haskell => tree_sitter_haskell::language(),
+
+            #[cfg(feature = "elixir")]
+            Self::Elixir => tree_sitter_elixir::language(),
+
+            #[cfg(feature = "markdown")]
+            Self::Markdown => tree_sitter_md::language(),
+
+            #[cfg(feature = "dockerfile")]
+            Self::Dockerfile => tree_sitter_dockerfile::language(),
+
+            #[cfg(feature = "sql")]
+            Self::Sql => tree_sitter_sql_bigquery::language(),
+
+            Self::Loaded(name) => {
+                grammar::language(name)
+                    .expect("`Loaded` languages should only be constructed once registered")
+            },
+        }
+    }
+
+    /// Retrieves the name of the [`RecognizedLanguage`], as used to refer to the language in
+    /// configuration files.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use antenna::RecognizedLanguage;
+    ///
+    /// assert_eq!(RecognizedLanguage::CSharp.name(), "c-sharp");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "rust")]
+            Self::Rust => "rust",
+
+            #[cfg(feature = "python")]
+            Self::Python => "python",
+
+            #[cfg(feature = "typescript")]
+            Self::TypeScript => "typescript",
+
+            #[cfg(feature = "typescript")]
+            Self::Tsx => "tsx",
+
+            #[cfg(feature = "javascript")]
+            Self::JavaScript => "javascript",
+
+            #[cfg(feature = "go")]
+            Self::Go => "go",
+
+            #[cfg(feature = "cpp")]
+            Self::Cpp => "cpp",
+
+            #[cfg(feature = "java")]
+            Self::Java => "java",
+
+            #[cfg(feature = "c")]
+            Self::C => "c",
+
+            #[cfg(feature = "ruby")]
+            Self::Ruby => "ruby",
+
+            #[cfg(feature = "html")]
+            Self::Html => "html",
+
+            #[cfg(feature = "css")]
+            Self::Css => "css",
+
+            #[cfg(feature = "swift")]
+            Self::Swift => "swift",
+
+            #[cfg(feature = "c-sharp")]
+            Self::CSharp => "c-sharp",
+
+            #[cfg(feature = "json")]
+            Self::Json => "json",
+
+            #[cfg(feature = "toml")]
+            Self::Toml => "toml",
+
+            #[cfg(feature = "yaml")]
+            Self::Yaml => "yaml",
+
+            #[cfg(feature = "bash")]
+            Self::Bash => "bash",
+
+            #[cfg(feature = "kotlin")]
+            Self::Kotlin => "kotlin",
+
+            #[cfg(feature = "php")]
+            Self::Php => "php",
+
+            #[cfg(feature = "lua")]
+            Self::Lua => "lua",
+
+            #[cfg(feature = "scala")]
+            Self::Scala => "scala",
+
+            #[cfg(feature = "haskell")]
+            Self::Haskell => "haskell",
+
+            #[cfg(feature = "elixir")]
+            Self::Elixir => "elixir",
+
+            #[cfg(feature = "markdown")]
+            Self::Markdown => "markdown",
+
+            #[cfg(feature = "dockerfile")]
+            Self::Dockerfile => "dockerfile",
+
+            #[cfg(feature = "sql")]
+            Self::Sql => "sql",
+
+            Self::Loaded(name) => name,
+        }
+    }
+
+    /// Attempts to construct a [`RecognizedLanguage`] from a language's [name](Self::name),
+    /// including the names of [loaded](grammar::register) grammars.
+    /// If the name is not mappable to a _[`RecognizedLanguage`]_, this function will return [`None`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use antenna::RecognizedLanguage;
+    ///
+    /// assert_eq!(
+    ///     RecognizedLanguage::from_name("Rust"),
+    ///     Some(RecognizedLanguage::Rust)
+    /// );
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            #[cfg(feature = "rust")]
+            "rust" => Some(Self::Rust),
+
+            #[cfg(feature = "python")]
+            "python" => Some(Self::Python),
+
+            #[cfg(feature = "typescript")]
+            "typescript" => Some(Self::TypeScript),
+
+            #[cfg(feature = "typescript")]
+            "tsx" => Some(Self::Tsx),
+
+            #[cfg(feature = "javascript")]
+            "javascript" => Some(Self::JavaScript),
+
+            #[cfg(feature = "go")]
+            "go" => Some(Self::Go),
+
+            #[cfg(feature = "cpp")]
+            "cpp" => Some(Self::Cpp),
+
+            #[cfg(feature = "java")]
+            "java" => Some(Self::Java),
+
+            #[cfg(feature = "c")]
+            "c" => Some(Self::C),
+
+            #[cfg(feature = "ruby")]
+            "ruby" => Some(Self::Ruby),
+
+            #[cfg(feature = "html")]
+            "html" => Some(Self::Html),
+
+            #[cfg(feature = "css")]
+            "css" => Some(Self::Css),
+
+            #[cfg(feature = "swift")]
+            "swift" => Some(Self::Swift),
+
+            #[cfg(feature = "c-sharp")]
+            "c-sharp" => Some(Self::CSharp),
+
+            #[cfg(feature = "json")]
+            "json" => Some(Self::Json),
+
+            #[cfg(feature = "toml")]
+            "toml" => Some(Self::Toml),
+
+            #[cfg(feature = "yaml")]
+            "yaml" => Some(Self::Yaml),
+
+            #[cfg(feature = "bash")]
+            "bash" => Some(Self::Bash),
+
+            #[cfg(feature = "kotlin")]
+            "kotlin" => Some(Self::Kotlin),
+
+            #[cfg(feature = "php")]
+            "php" => Some(Self::Php),
+
+            #[cfg(feature = "lua")]
+            "lua" => Some(Self::Lua),
+
+            #[cfg(feature = "scala")]
+            "scala" => Some(Self::Scala),
+
+            #[cfg(feature = "haskell")]
+            "haskell" => Some(Self::Haskell),
+
+            #[cfg(feature = "elixir")]
+            "elixir" => Some(Self::Elixir),
+
+            #[cfg(feature = "markdown")]
+            "markdown" => Some(Self::Markdown),
+
+            #[cfg(feature = "dockerfile")]
+            "dockerfile" => Some(Self::Dockerfile),
+
+            #[cfg(feature = "sql")]
+            "sql" => Some(Self::Sql),
+
+            _ => grammar::find(name),
         }
     }
 
@@ -264,6 +570,36 @@ impl RecognizedLanguage {
             #[cfg(feature = "yaml")]
             "yaml" | "yml" => Some(Self::Yaml),
 
+            #[cfg(feature = "bash")]
+            "sh" | "bash" => Some(Self::Bash),
+
+            #[cfg(feature = "kotlin")]
+            "kt" | "kts" => Some(Self::Kotlin),
+
+            #[cfg(feature = "php")]
+            "php" | "phtml" => Some(Self::Php),
+
+            #[cfg(feature = "lua")]
+            "lua" => Some(Self::Lua),
+
+            #[cfg(feature = "scala")]
+            "scala" | "sc" | "sbt" => Some(Self::Scala),
+
+            #[cfg(feature = "haskell")]
+            "hs" => Some(Self::Haskell),
+
+            #[cfg(feature = "elixir")]
+            "ex" | "exs" => Some(Self::Elixir),
+
+            #[cfg(feature = "markdown")]
+            "md" | "markdown" => Some(Self::Markdown),
+
+            #[cfg(feature = "dockerfile")]
+            "dockerfile" => Some(Self::Dockerfile),
+
+            #[cfg(feature = "sql")]
+            "sql" => Some(Self::Sql),
+
             _ => None,
         }
     }
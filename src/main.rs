@@ -1,79 +1,168 @@
-use std::{fs, io::Write};
-
-use antenna::{configuration::AntennaOutputMode, process::index::Indexer, AntennaResult};
-use args::AntennaArguments;
-use clap::Parser;
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use antenna::{
+    baseline::Baseline,
+    cache::Cache,
+    changes::Changes,
+    configuration::{AntennaConfiguration, AntennaErrorPolicy},
+    AntennaError, AntennaResult,
+};
+use args::{AntennaArguments, AntennaCommand};
+use clap::{parser::ValueSource, ArgMatches, CommandFactory, FromArgMatches};
 
 mod args;
+mod commands;
 
-fn main() -> AntennaResult<()> {
+fn main() -> AntennaResult<ExitCode> {
+    let matches = AntennaArguments::command().get_matches();
     let AntennaArguments {
         configuration_file: settings_file,
-    } = AntennaArguments::parse();
-
-    let configuration_file = fs::read_to_string(settings_file)?;
-    let configuration = serde_yaml::from_str(&configuration_file)?;
-
-    let indexer = Indexer::default().index(&configuration)?;
-
-    for antenna_query in configuration.queries {
-        let out_queries = antenna::process::execute_antenna_query(&antenna_query, &indexer)?;
-
-        if let Some(output_modes) = &antenna_query.output {
-            for output_mode in output_modes {
-                match output_mode {
-                    AntennaOutputMode::Occurrences => {
-                        println!("{}", &antenna_query.name);
-
-                        for out_query in &out_queries {
-                            println!("> {:?} = `{}`", out_query.path, out_query.matches.len());
-                        }
-                    },
-
-                    AntennaOutputMode::Json {
-                        path,
-                        require_matches,
-                    } => {
-                        let mut file =
-                            fs::OpenOptions::new().create(true).write(true).open(path)?;
-
-                        let json = match require_matches {
-                            false => serde_json::to_string_pretty(&out_queries)?,
-                            true => {
-                                let out_queries = out_queries
-                                    .split(|x| x.matches.is_empty())
-                                    .flatten()
-                                    .collect::<Vec<_>>();
-
-                                serde_json::to_string_pretty(&out_queries)?
-                            },
-                        };
-
-                        file.write_all(json.as_bytes())?;
-                    },
-
-                    AntennaOutputMode::Csv { path } => {
-                        let file =
-                            fs::OpenOptions::new().create(true).write(true).open(path)?;
-
-                        let mut csv_writer = csv::Writer::from_writer(file);
-
-                        for out_query in &out_queries {
-                            for out_match in &out_query.matches {
-                                let rows = antenna::out::csv::Capture::from_out_captures(
-                                    &antenna_query.name,
-                                    &out_query.path,
-                                    &out_match.captures,
-                                );
-
-                                csv_writer.serialize(rows)?;
-                            }
-                        }
-
-                        csv_writer.flush()?;
-                    },
+        error_policy,
+        jobs,
+        fail_on,
+        baseline,
+        changed_since,
+        cache_dir,
+        command,
+    } = AntennaArguments::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+
+    if !matches!(command, None | Some(AntennaCommand::Baseline(_))) {
+        reject_options(
+            &matches,
+            &["cache_dir"],
+            "when executing every query or writing a baseline",
+        )?;
+    }
+
+    if !matches!(command, None | Some(AntennaCommand::Watch)) {
+        reject_options(
+            &matches,
+            &["baseline"],
+            "when executing or watching every query",
+        )?;
+    }
+
+    if command.is_some() {
+        reject_options(&matches, &["changed_since"], "when executing every query")?;
+    }
+
+    if let Some(jobs) = jobs {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build_global()
+            .map_err(|err| {
+                AntennaError::Antenna {
+                    message: format!("Failed to build thread pool: {err}"),
                 }
-            }
+            })?;
+    }
+
+    match command {
+        Some(AntennaCommand::Query(arguments)) => {
+            let configuration =
+                read_optional_configuration(&matches, settings_file, error_policy)?;
+
+            commands::query::execute(
+                configuration,
+                arguments,
+                error_policy.unwrap_or_default(),
+            )?;
+            Ok(ExitCode::SUCCESS)
+        },
+
+        Some(AntennaCommand::Baseline(arguments)) => {
+            let configuration = read_configuration(settings_file, error_policy)?;
+            let cache = cache_dir.map(Cache::open).transpose()?;
+
+            commands::baseline::execute(configuration, arguments, cache)?;
+            Ok(ExitCode::SUCCESS)
+        },
+
+        Some(AntennaCommand::Watch) => {
+            let configuration = read_configuration(settings_file, error_policy)?;
+            let baseline = baseline.map(Baseline::read).transpose()?;
+
+            commands::watch::execute(configuration, fail_on, baseline)?;
+            Ok(ExitCode::SUCCESS)
+        },
+
+        Some(AntennaCommand::Fix(arguments)) => {
+            let configuration = read_configuration(settings_file, error_policy)?;
+            commands::fix::execute(configuration, arguments)
+        },
+
+        Some(AntennaCommand::Test(arguments)) => {
+            let configuration = read_configuration(settings_file, error_policy)?;
+            commands::test::execute(configuration, arguments)
+        },
+
+        Some(AntennaCommand::Tree(arguments)) => {
+            let configuration =
+                read_optional_configuration(&matches, settings_file, error_policy)?;
+
+            commands::tree::execute(configuration, arguments)?;
+            Ok(ExitCode::SUCCESS)
+        },
+
+        Some(AntennaCommand::Lsp) => {
+            let configuration = read_configuration(settings_file, error_policy)?;
+            commands::lsp::execute(configuration)
+        },
+
+        None => {
+            let configuration = read_configuration(settings_file, error_policy)?;
+            let baseline = baseline.map(Baseline::read).transpose()?;
+            let changes = changed_since.as_deref().map(Changes::since).transpose()?;
+            let cache = cache_dir.map(Cache::open).transpose()?;
+
+            commands::run::execute(configuration, fail_on, baseline, changes, cache)
+        },
+    }
+}
+
+/// Reads the [`AntennaConfiguration`] at the given `path`, overriding its `error_policy` with the
+/// given `error_policy` if present.
+fn read_configuration(
+    path: PathBuf,
+    error_policy: Option<AntennaErrorPolicy>,
+) -> AntennaResult<AntennaConfiguration> {
+    let configuration_file = fs::read_to_string(path)?;
+    let mut configuration: AntennaConfiguration = serde_yaml::from_str(&configuration_file)?;
+
+    if let Some(error_policy) = error_policy {
+        configuration.error_policy = error_policy;
+    }
+
+    Ok(configuration)
+}
+
+/// Reads the [`AntennaConfiguration`] at the given `path` like [`read_configuration`], unless it
+/// does not exist and the path is the default one, in which case [`None`] is returned. A missing
+/// configuration file that was explicitly passed is an error.
+fn read_optional_configuration(
+    matches: &ArgMatches,
+    path: PathBuf,
+    error_policy: Option<AntennaErrorPolicy>,
+) -> AntennaResult<Option<AntennaConfiguration>> {
+    let is_default =
+        matches.value_source("configuration_file") == Some(ValueSource::DefaultValue);
+
+    match is_default && !path.exists() {
+        true => Ok(None),
+        false => Ok(Some(read_configuration(path, error_policy)?)),
+    }
+}
+
+/// Returns an error if any of the options with the given `ids` was passed on the command line to
+/// a subcommand that does not support it, the error describes where the option is supported via
+/// `supported`. Values taken from the environment are ignored instead, as those usually apply to
+/// every invocation.
+fn reject_options(matches: &ArgMatches, ids: &[&str], supported: &str) -> AntennaResult<()> {
+    for id in ids {
+        if matches.value_source(id) == Some(ValueSource::CommandLine) {
+            return Err(AntennaError::Antenna {
+                message: format!("`--{}` is only supported {supported}", id.replace('_', "-")),
+            });
         }
     }
 
@@ -1,19 +1,173 @@
-use std::{borrow::Cow, path::Path};
+use std::{
+    borrow::Cow,
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::Path,
+    process,
+};
 
 use serde::{Deserialize, Serialize};
 
+use crate::{AntennaError, AntennaResult};
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case", rename = "query", tag = "type")]
 pub struct Query<'a> {
     pub name: Cow<'a, str>,
     pub path: Cow<'a, Path>,
     pub matches: Vec<Match>,
+
+    /// Matches suppressed via comments in the file, which are excluded from `matches`.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub suppressed: Vec<Match>,
+}
+
+impl Query<'_> {
+    /// Converts the [`Query`] into one owning its name and path.
+    pub fn into_owned(self) -> Query<'static> {
+        Query {
+            name: Cow::Owned(self.name.into_owned()),
+            path: Cow::Owned(self.path.into_owned()),
+            matches: self.matches,
+            suppressed: self.suppressed,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case", rename = "match", tag = "type")]
 pub struct Match {
     pub captures: Vec<Capture>,
+
+    /// The query's message rendered for this match, see [`Match::render_message`].
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub message: Option<String>,
+
+    /// A position independent fingerprint of the match, see [`crate::baseline::fingerprint`].
+    #[serde(default)]
+    pub fingerprint: String,
+}
+
+impl Match {
+    /// Renders a message `template`, replacing `{capture}` placeholders with the text of the
+    /// first capture of the same name. Placeholders without a matching capture are left as is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use antenna::out::{Capture, Match};
+    ///
+    /// let out_match = Match {
+    ///     captures: vec![Capture {
+    ///         text: String::from("main"),
+    ///         name: String::from("name"),
+    ///         start_column: 3,
+    ///         start_line: 0,
+    ///         end_column: 7,
+    ///         end_line: 0,
+    ///         start_utf16_column: 3,
+    ///         end_utf16_column: 7,
+    ///     }],
+    ///     message: None,
+    ///     fingerprint: String::new(),
+    /// };
+    ///
+    /// assert_eq!(
+    ///     out_match.render_message("`{name}` is {missing}"),
+    ///     "`main` is {missing}"
+    /// );
+    /// ```
+    pub fn render_message(&self, template: &str) -> String {
+        let mut rendered = String::with_capacity(template.len());
+        let mut remaining = template;
+
+        while let Some(start) = remaining.find('{') {
+            rendered.push_str(&remaining[..start]);
+            remaining = &remaining[start..];
+
+            let Some(end) = remaining.find('}') else {
+                break;
+            };
+
+            let name = &remaining[1..end];
+
+            match self.captures.iter().find(|x| x.name == name) {
+                Some(capture) => rendered.push_str(&capture.text),
+                None => rendered.push_str(&remaining[..=end]),
+            }
+
+            remaining = &remaining[end + 1..];
+        }
+
+        rendered.push_str(remaining);
+        rendered
+    }
+
+    /// Renders a fix `template`, replacing `@capture` references with the text of the first
+    /// capture of the same name and `@@` with a literal `@`. The longest capture name following
+    /// an `@` is used, so `@recv.unwrap()` refers to `recv` unless a capture named `recv.unwrap`
+    /// exists. References without a matching capture are left as is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use antenna::out::{Capture, Match};
+    ///
+    /// let out_match = Match {
+    ///     captures: vec![Capture {
+    ///         text: String::from("value"),
+    ///         name: String::from("recv"),
+    ///         start_column: 0,
+    ///         start_line: 0,
+    ///         end_column: 5,
+    ///         end_line: 0,
+    ///         start_utf16_column: 0,
+    ///         end_utf16_column: 5,
+    ///     }],
+    ///     message: None,
+    ///     fingerprint: String::new(),
+    /// };
+    ///
+    /// assert_eq!(
+    ///     out_match.render_fix("@recv.expect(\"@@missing\")"),
+    ///     "value.expect(\"@missing\")"
+    /// );
+    /// ```
+    pub fn render_fix(&self, template: &str) -> String {
+        let mut rendered = String::with_capacity(template.len());
+        let mut remaining = template;
+
+        while let Some(start) = remaining.find('@') {
+            rendered.push_str(&remaining[..start]);
+            remaining = &remaining[start + 1..];
+
+            if let Some(escaped) = remaining.strip_prefix('@') {
+                rendered.push('@');
+                remaining = escaped;
+                continue;
+            }
+
+            let capture = self
+                .captures
+                .iter()
+                .rev()
+                .filter(|x| !x.name.is_empty() && remaining.starts_with(&x.name))
+                .max_by_key(|x| x.name.len());
+
+            match capture {
+                Some(capture) => {
+                    rendered.push_str(&capture.text);
+                    remaining = &remaining[capture.name.len()..];
+                },
+
+                None => rendered.push('@'),
+            }
+        }
+
+        rendered.push_str(remaining);
+        rendered
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
@@ -25,6 +179,123 @@ pub struct Capture {
     pub start_line: usize,
     pub end_column: usize,
     pub end_line: usize,
+
+    /// Columns of the start and end of the capture in UTF-16 code units, as expected by SARIF,
+    /// whereas `start_column` and `end_column` are byte offsets into their lines.
+    pub start_utf16_column: usize,
+    pub end_utf16_column: usize,
+}
+
+/// Writes the number of matches in each of the `out_queries` to the `writer`, in a human readable
+/// format, followed by the number of suppressed matches if there are any.
+pub fn write_occurrences<W>(
+    mut writer: W,
+    name: &str,
+    out_queries: &[Query],
+) -> AntennaResult<()>
+where
+    W: Write,
+{
+    writeln!(writer, "{name}")?;
+
+    for out_query in out_queries {
+        write!(
+            writer,
+            "> {:?} = `{}`",
+            out_query.path,
+            out_query.matches.len()
+        )?;
+
+        match out_query.suppressed.len() {
+            0 => writeln!(writer)?,
+            suppressed => writeln!(writer, " (`{suppressed}` suppressed)")?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the `out_queries` to the `writer` as JSON, omitting queries without any matches if
+/// `require_matches` is `true`.
+pub fn write_json<W>(
+    mut writer: W,
+    out_queries: &[Query],
+    require_matches: bool,
+) -> AntennaResult<()>
+where
+    W: Write,
+{
+    let json = match require_matches {
+        false => serde_json::to_string_pretty(&out_queries)?,
+        true => {
+            let out_queries = out_queries
+                .split(|x| x.matches.is_empty())
+                .flatten()
+                .collect::<Vec<_>>();
+
+            serde_json::to_string_pretty(&out_queries)?
+        },
+    };
+
+    writer.write_all(json.as_bytes())?;
+    Ok(())
+}
+
+/// Writes the file at `path` atomically, by passing a temporary sibling file to `write` and
+/// renaming it to `path` once `write` succeeds. Missing parent directories are created.
+pub fn write_file_atomically<P, F>(path: P, write: F) -> AntennaResult<()>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&mut File) -> AntennaResult<()>,
+{
+    let path = path.as_ref();
+    create_parent_directories(path)?;
+
+    let file_name = path.file_name().ok_or(AntennaError::Antenna {
+        message: format!("`{path:?}` is not a path to a file"),
+    })?;
+
+    let mut temporary_name = OsString::from(".");
+    temporary_name.push(file_name);
+    temporary_name.push(format!(".{}.tmp", process::id()));
+
+    let temporary_path = path.with_file_name(temporary_name);
+
+    let result = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&temporary_path)
+        .map_err(AntennaError::from)
+        .and_then(|mut file| {
+            write(&mut file)?;
+            file.sync_all()?;
+            Ok(fs::rename(&temporary_path, path)?)
+        });
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary_path);
+    }
+
+    result
+}
+
+/// Opens the file at `path` for appending, creating it and any missing parent directories if
+/// they do not exist.
+pub fn open_file_for_append<P>(path: P) -> AntennaResult<File>
+where
+    P: AsRef<Path>,
+{
+    create_parent_directories(path.as_ref())?;
+    Ok(OpenOptions::new().create(true).append(true).open(path)?)
+}
+
+/// Creates the parent directories of `path` if they do not exist.
+fn create_parent_directories(path: &Path) -> AntennaResult<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => Ok(fs::create_dir_all(parent)?),
+        _ => Ok(()),
+    }
 }
 
 pub mod csv {
@@ -42,6 +313,7 @@ pub mod csv {
         pub start_line: usize,
         pub end_column: usize,
         pub end_line: usize,
+        pub suppressed: bool,
     }
 
     impl<'a> Capture<'a> {
@@ -49,6 +321,7 @@ pub mod csv {
             query: &'a str,
             path: &'a Path,
             out_captures: &'a [super::Capture],
+            suppressed: bool,
         ) -> Vec<Self> {
             let out_csv_captures = out_captures.iter().enumerate().map(|(index, capture)| {
                 Capture {
@@ -61,10 +334,315 @@ pub mod csv {
                     start_line: capture.start_line,
                     end_column: capture.end_column,
                     end_line: capture.end_line,
+                    suppressed,
                 }
             });
 
             out_csv_captures.collect()
         }
     }
+
+    /// Writes the captures of every match in the `out_queries` to the `writer` as CSV, preceded
+    /// by a header row if `headers` is `true`. Captures of suppressed matches are written as well,
+    /// marked via the `suppressed` column.
+    pub fn write_csv<W>(
+        writer: W,
+        name: &str,
+        out_queries: &[super::Query],
+        headers: bool,
+    ) -> crate::AntennaResult<()>
+    where
+        W: std::io::Write,
+    {
+        let mut csv_writer = ::csv::WriterBuilder::new()
+            .has_headers(headers)
+            .from_writer(writer);
+
+        for out_query in out_queries {
+            let matches = out_query.matches.iter().map(|x| (x, false));
+            let suppressed = out_query.suppressed.iter().map(|x| (x, true));
+
+            for (out_match, suppressed) in matches.chain(suppressed) {
+                let rows = Capture::from_out_captures(
+                    name,
+                    &out_query.path,
+                    &out_match.captures,
+                    suppressed,
+                );
+
+                csv_writer.serialize(rows)?;
+            }
+        }
+
+        csv_writer.flush()?;
+        Ok(())
+    }
+}
+
+pub mod sarif {
+    use serde::{Deserialize, Serialize};
+
+    /// URI of the SARIF 2.1.0 JSON schema.
+    const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+    /// Severity of a SARIF [`Result`], see the `level` property in the SARIF specification.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Level {
+        None,
+        Note,
+        #[default]
+        Warning,
+        Error,
+    }
+
+    /// Describes the rule a set of [`Queries`](super::Query) should be reported as.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct RuleOptions<'a> {
+        pub id: &'a str,
+        pub name: &'a str,
+        pub message: &'a str,
+        pub level: Level,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    pub struct Log {
+        #[serde(rename = "$schema")]
+        pub schema: String,
+        pub version: String,
+        pub runs: Vec<Run>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Run {
+        pub tool: Tool,
+        pub results: Vec<Result>,
+
+        #[serde(default)]
+        pub column_kind: ColumnKind,
+    }
+
+    /// Unit the columns of every [`Region`] in a [`Run`] are counted in, see the `columnKind`
+    /// property in the SARIF specification.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub enum ColumnKind {
+        #[default]
+        Utf16CodeUnits,
+        UnicodeCodePoints,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    pub struct Tool {
+        pub driver: Driver,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Driver {
+        pub name: String,
+        pub version: String,
+        pub rules: Vec<Rule>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Rule {
+        pub id: String,
+        pub name: String,
+        pub short_description: Message,
+        pub default_configuration: Configuration,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    pub struct Configuration {
+        pub level: Level,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Result {
+        pub rule_id: String,
+        pub rule_index: usize,
+        pub level: Level,
+        pub message: Message,
+        pub locations: Vec<Location>,
+
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub related_locations: Vec<Location>,
+
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        pub suppressions: Vec<Suppression>,
+    }
+
+    /// Describes why a [`Result`] is suppressed, see the `suppression` object in the SARIF
+    /// specification.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    pub struct Suppression {
+        pub kind: SuppressionKind,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub enum SuppressionKind {
+        InSource,
+        External,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    pub struct Message {
+        pub text: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Location {
+        pub physical_location: PhysicalLocation,
+
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        pub message: Option<Message>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct PhysicalLocation {
+        pub artifact_location: ArtifactLocation,
+        pub region: Region,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    pub struct ArtifactLocation {
+        pub uri: String,
+    }
+
+    /// A range within an artifact, lines and columns are one-based. Columns are counted in the
+    /// [`ColumnKind`] of the [`Run`].
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Region {
+        pub start_line: usize,
+        pub start_column: usize,
+        pub end_line: usize,
+        pub end_column: usize,
+    }
+
+    impl Default for Log {
+        fn default() -> Self {
+            Self {
+                schema: String::from(SCHEMA),
+                version: String::from("2.1.0"),
+                runs: vec![Run {
+                    tool: Tool {
+                        driver: Driver {
+                            name: String::from(env!("CARGO_PKG_NAME")),
+                            version: String::from(env!("CARGO_PKG_VERSION")),
+                            rules: Vec::new(),
+                        },
+                    },
+                    results: Vec::new(),
+                    column_kind: ColumnKind::Utf16CodeUnits,
+                }],
+            }
+        }
+    }
+
+    impl Log {
+        /// Adds a [`Rule`] described by the given `options` to the [`Log`], alongside a
+        /// [`Result`] for every match in the `out_queries`. Suppressed matches are added as
+        /// results with an in source [`Suppression`]. Columns are given in UTF-16 code units.
+        pub fn push_rule(&mut self, options: RuleOptions, out_queries: &[super::Query]) {
+            let run = &mut self.runs[0];
+            let rule_index = run.tool.driver.rules.len();
+
+            run.tool.driver.rules.push(Rule {
+                id: options.id.to_owned(),
+                name: options.name.to_owned(),
+                short_description: Message {
+                    text: options.message.to_owned(),
+                },
+                default_configuration: Configuration {
+                    level: options.level,
+                },
+            });
+
+            for out_query in out_queries {
+                let uri = out_query.path.to_string_lossy().replace('\\', "/");
+
+                let region = |start: (usize, usize), end: (usize, usize)| {
+                    Region {
+                        start_line: start.0 + 1,
+                        start_column: start.1 + 1,
+                        end_line: end.0 + 1,
+                        end_column: end.1 + 1,
+                    }
+                };
+
+                let matches = out_query.matches.iter().map(|x| (x, false));
+                let suppressed = out_query.suppressed.iter().map(|x| (x, true));
+
+                for (out_match, suppressed) in matches.chain(suppressed) {
+                    let start = out_match
+                        .captures
+                        .iter()
+                        .map(|x| (x.start_line, x.start_utf16_column))
+                        .min();
+
+                    let end = out_match
+                        .captures
+                        .iter()
+                        .map(|x| (x.end_line, x.end_utf16_column))
+                        .max();
+
+                    let (Some(start), Some(end)) = (start, end) else {
+                        continue;
+                    };
+
+                    let location = |region, message| {
+                        Location {
+                            physical_location: PhysicalLocation {
+                                artifact_location: ArtifactLocation { uri: uri.clone() },
+                                region,
+                            },
+                            message,
+                        }
+                    };
+
+                    let related_locations = out_match.captures.iter().map(|capture| {
+                        location(
+                            region(
+                                (capture.start_line, capture.start_utf16_column),
+                                (capture.end_line, capture.end_utf16_column),
+                            ),
+                            Some(Message {
+                                text: capture.name.clone(),
+                            }),
+                        )
+                    });
+
+                    run.results.push(Result {
+                        rule_id: options.id.to_owned(),
+                        rule_index,
+                        level: options.level,
+                        message: Message {
+                            text: out_match
+                                .message
+                                .clone()
+                                .unwrap_or_else(|| options.message.to_owned()),
+                        },
+                        locations: vec![location(region(start, end), None)],
+                        related_locations: related_locations.collect(),
+                        suppressions: match suppressed {
+                            true => {
+                                vec![Suppression {
+                                    kind: SuppressionKind::InSource,
+                                }]
+                            },
+                            false => Vec::new(),
+                        },
+                    });
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,150 @@
+use std::{
+    collections::HashSet,
+    path::{Component, Path, PathBuf},
+};
+
+use glob::{MatchOptions, Pattern};
+use ignore::WalkBuilder;
+
+use crate::{configuration::AntennaQuery, AntennaError, AntennaResult};
+
+/// [`MatchOptions`] mirroring the matching behaviour of [`glob::glob`].
+pub(crate) const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Discovers the paths of all files matched by the `include` patterns of an [`AntennaQuery`],
+/// excluding any path matched by one of its `exclude` patterns.
+///
+/// If the [`AntennaQuery`] respects ignore files, paths ignored by `.gitignore`, `.ignore`,
+/// `.git/info/exclude` or global git excludes are excluded as well.
+pub fn discover_paths(antenna_query: &AntennaQuery) -> AntennaResult<HashSet<PathBuf>> {
+    let exclude = antenna_query
+        .exclude
+        .iter()
+        .map(|x| Pattern::new(normalize_pattern(x)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut paths = HashSet::new();
+    let mut errors = Vec::new();
+
+    for include in &antenna_query.include {
+        let discovered = match antenna_query.respect_ignore {
+            true => walk(include),
+            false => glob(include),
+        };
+
+        for path in discovered? {
+            match path {
+                Ok(path) => {
+                    if !is_excluded(&exclude, &path) {
+                        paths.insert(path);
+                    }
+                },
+
+                Err(err) => errors.push(err),
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(AntennaError::Collection { errors });
+    }
+
+    Ok(paths)
+}
+
+/// Determines whether the given `path` is matched by one of the `include` patterns of an
+/// [`AntennaQuery`] and none of its `exclude` patterns, without consulting ignore files.
+pub fn is_included<P>(antenna_query: &AntennaQuery, path: P) -> AntennaResult<bool>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let path = path.strip_prefix(".").unwrap_or(path);
+
+    for include in &antenna_query.include {
+        if Pattern::new(normalize_pattern(include))?.matches_path_with(path, MATCH_OPTIONS) {
+            let exclude = antenna_query
+                .exclude
+                .iter()
+                .map(|x| Pattern::new(normalize_pattern(x)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            return Ok(!is_excluded(&exclude, path));
+        }
+    }
+
+    Ok(false)
+}
+
+/// Discovers paths matching the `include` pattern via [`glob::glob`].
+fn glob(include: &str) -> AntennaResult<Vec<AntennaResult<PathBuf>>> {
+    let paths = glob::glob(include)?.map(|x| x.map_err(AntennaError::from));
+    Ok(paths.collect())
+}
+
+/// Discovers files matching the `include` pattern by walking the pattern's base directory,
+/// skipping any entries ignored by ignore files.
+fn walk(include: &str) -> AntennaResult<Vec<AntennaResult<PathBuf>>> {
+    let pattern = Pattern::new(normalize_pattern(include))?;
+
+    let walker = WalkBuilder::new(base_directory(include))
+        .hidden(false)
+        .filter_entry(|x| x.file_name() != ".git")
+        .build();
+
+    let paths = walker.filter_map(|entry| {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                return Some(Err(AntennaError::Antenna {
+                    message: format!("Failed to walk `{include}`: {err}"),
+                }))
+            },
+        };
+
+        if !entry.file_type().is_some_and(|x| x.is_file()) {
+            return None;
+        }
+
+        let path = entry.path();
+        let path = path.strip_prefix(".").unwrap_or(path);
+
+        pattern
+            .matches_path_with(path, MATCH_OPTIONS)
+            .then(|| Ok(path.to_path_buf()))
+    });
+
+    Ok(paths.collect())
+}
+
+/// Determines the directory a walk should start from, being the leading components of the
+/// `include` pattern that do not contain any wildcards.
+fn base_directory(include: &str) -> PathBuf {
+    let base = Path::new(include)
+        .components()
+        .take_while(|x| !x.as_os_str().to_string_lossy().contains(['*', '?', '[']))
+        .collect::<PathBuf>();
+
+    match base.components().next() {
+        Some(Component::RootDir | Component::Prefix(_) | Component::CurDir) => base,
+        Some(_) => Path::new(".").join(base),
+        None => PathBuf::from("."),
+    }
+}
+
+/// Determines if the given `path` is matched by any of the `exclude` patterns.
+fn is_excluded(exclude: &[Pattern], path: &Path) -> bool {
+    let path = path.strip_prefix(".").unwrap_or(path);
+    exclude
+        .iter()
+        .any(|x| x.matches_path_with(path, MATCH_OPTIONS))
+}
+
+/// Strips a leading `./` from a pattern, as paths yielded by [`glob::glob`] never contain one.
+pub(crate) fn normalize_pattern(pattern: &str) -> &str {
+    pattern.strip_prefix("./").unwrap_or(pattern)
+}
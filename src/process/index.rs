@@ -1,18 +1,23 @@
 use std::{
     collections::{HashMap, HashSet},
-    fs::OpenOptions,
+    fs::{self, OpenOptions},
     hash::Hash,
-    io::prelude::*,
+    io::{prelude::*, ErrorKind},
     path::{Path, PathBuf},
     sync::Arc,
 };
 
 use rayon::prelude::*;
-use tree_sitter::{Parser, Tree};
+use tree_sitter::{InputEdit, Parser, Point, Tree};
 
+use super::{discover, inject::Injection, suppress::Suppressions};
 use crate::{
-    configuration::{AntennaConfiguration, AntennaQuery},
-    AntennaError, AntennaResult, RecognizedLanguage,
+    cache::Cache,
+    changes::Changes,
+    configuration::{AntennaConfiguration, AntennaErrorPolicy, AntennaQuery},
+    detect::LanguageMap,
+    diagnostic::Diagnostic,
+    out, AntennaError, AntennaResult, RecognizedLanguage,
 };
 
 #[derive(Debug, Default)]
@@ -24,54 +29,86 @@ pub struct Indexer {
     files_by_path: HashMap<PathBuf, Arc<IndexedFile>>,
 
     files: HashSet<Arc<IndexedFile>>,
+
+    diagnostics: Vec<Diagnostic>,
+
+    changes: Option<Changes>,
+
+    cache: Option<Cache>,
+
+    cached_by_query_name: HashMap<String, Vec<out::Query<'static>>>,
 }
 
 impl Indexer {
+    /// Restricts the files indexed via [`Indexer::index`] to files with the given [`Changes`].
+    pub fn with_changes(self, changes: Changes) -> Self {
+        Self {
+            changes: Some(changes),
+            ..self
+        }
+    }
+
+    /// Skips files whose results are stored in the given [`Cache`] when indexing via
+    /// [`Indexer::index`], using the cached results instead.
+    pub fn with_cache(self, cache: Cache) -> Self {
+        Self {
+            cache: Some(cache),
+            ..self
+        }
+    }
+
     /// Consumes the [`Indexer`], creating indicies for all [queries](Query) and [files](IndexedFile) found in the given [`configuration`](AntennaConfiguration).
+    ///
+    /// Files that fail to be indexed are handled according to the configuration's
+    /// [`AntennaErrorPolicy`], either failing the entire index, being recorded as
+    /// [diagnostics](Diagnostic) or being dropped.
     pub fn index(self, configuration: &AntennaConfiguration) -> AntennaResult<Self> {
         let mut files_by_query_name = self.files_by_query_name;
         let mut files_by_path = self.files_by_path;
 
         let mut files = self.files;
-        let mut errors = Vec::new();
+        let mut diagnostics = self.diagnostics;
+        let changes = self.changes;
+        let cache = self.cache;
+        let mut cached_by_query_name = self.cached_by_query_name;
+
+        let error_policy = configuration.error_policy;
+        let language_map = LanguageMap::from_configuration(configuration)?;
 
         let indices = configuration
             .queries
             .par_iter()
-            .map(Self::map_antenna_queries)
-            .collect::<Vec<_>>();
+            .map(|x| {
+                Self::map_antenna_queries(
+                    x,
+                    &language_map,
+                    changes.as_ref(),
+                    cache.as_ref(),
+                    error_policy,
+                )
+            })
+            .collect::<AntennaResult<Vec<_>>>()?;
 
         for index in indices {
-            match index {
-                Ok((indexed_files, (associated_query, associated_include))) => {
-                    files.extend(indexed_files);
-
-                    let associated_glob =
-                        glob::glob(&associated_include)?.collect::<Result<HashSet<_>, _>>()?;
-
-                    files.iter().for_each(|x| {
-                        if associated_glob.contains(&x.path) {
-                            match files_by_query_name.get_mut(&associated_query) {
-                                Some(collection) => {
-                                    collection.insert(Arc::clone(x));
-                                },
-
-                                None => {
-                                    files_by_query_name.insert(
-                                        associated_query.to_owned(),
-                                        HashSet::from_iter(vec![Arc::clone(x)]),
-                                    );
-                                },
-                            }
-                        }
+            for file in index.files {
+                files_by_query_name
+                    .entry(index.name.to_owned())
+                    .or_default()
+                    .insert(Arc::clone(&file));
+
+                files_by_path.insert(file.path.to_owned(), Arc::clone(&file));
+                files.insert(file);
+            }
 
-                        files_by_path.insert(x.path.to_owned(), Arc::clone(x));
-                    });
-                },
+            if !index.cached.is_empty() {
+                cached_by_query_name
+                    .entry(index.name.to_owned())
+                    .or_default()
+                    .extend(index.cached);
+            }
 
-                Err(err) => {
-                    errors.push(err);
-                },
+            if error_policy != AntennaErrorPolicy::Ignore {
+                diagnostics.extend(index.diagnostics);
             }
         }
 
@@ -79,6 +116,10 @@ impl Indexer {
             files_by_query_name,
             files_by_path,
             files,
+            diagnostics,
+            changes,
+            cache,
+            cached_by_query_name,
         };
 
         Ok(constructed)
@@ -109,64 +150,368 @@ impl Indexer {
     pub fn files(&self) -> impl Iterator<Item = &IndexedFile> {
         self.files.iter().map(|x| x.as_ref())
     }
+
+    /// Retrieves the [diagnostics](Diagnostic) collected while indexing.
+    pub fn diagnostics(&self) -> &[Diagnostic] { &self.diagnostics }
+
+    /// Retrieves the [`Changes`] indexed files are restricted to, if any.
+    pub fn changes(&self) -> Option<&Changes> { self.changes.as_ref() }
+
+    /// Retrieves the [`Cache`] results are read from and stored in, if any.
+    pub fn cache(&self) -> Option<&Cache> { self.cache.as_ref() }
+
+    /// Retrieves the cached results of the query of the given `query_name` for files that were
+    /// skipped while indexing, see [`Indexer::with_cache`].
+    pub fn get_cached_by_query_name<S>(&self, query_name: S) -> &[out::Query<'static>]
+    where
+        S: AsRef<str>,
+    {
+        self.cached_by_query_name
+            .get(query_name.as_ref())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Updates the index after the files at the given `paths` were modified, created or removed,
+    /// returning the names of the queries whose files changed.
+    ///
+    /// Modified files are re-parsed incrementally in every language they are indexed in, reusing
+    /// their previous trees, and removed files are dropped. Files that are not indexed yet are
+    /// indexed if one of the queries in the given [`configuration`](AntennaConfiguration)
+    /// discovers them, in the language each of those queries parses them as.
+    ///
+    /// Files that fail to be read or parsed are dropped from the index and handled according to
+    /// the configuration's [`AntennaErrorPolicy`], once every other file was updated. Collected
+    /// [diagnostics](Diagnostic) are retrieved via [`Indexer::take_diagnostics`].
+    pub fn update<I>(
+        &mut self,
+        configuration: &AntennaConfiguration,
+        paths: I,
+    ) -> AntennaResult<HashSet<String>>
+    where
+        I: IntoIterator<Item = PathBuf>,
+    {
+        let mut affected = HashSet::new();
+        let mut created = HashSet::new();
+        let mut diagnostics = Vec::new();
+
+        for path in paths {
+            let previous = self
+                .files
+                .iter()
+                .filter(|x| x.path == path)
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if previous.is_empty() {
+                if path.is_file() {
+                    created.insert(path);
+                }
+
+                continue;
+            }
+
+            let mut read_error = None;
+            let content = match fs::read(&path) {
+                Ok(content) => Some(content),
+                Err(err) if err.kind() == ErrorKind::NotFound => None,
+                Err(err) => {
+                    read_error = Some(AntennaError::from(err));
+                    None
+                },
+            };
+
+            let mut names = Vec::new();
+            self.files_by_path.remove(&path);
+
+            for previous in previous {
+                let parsed = content.clone().map(|content| {
+                    let language = previous.recognized_language;
+                    IndexedFile::parse(&path, content, language, Some(&previous))
+                });
+
+                let (current, parse_error) = match parsed {
+                    Some(Ok(current)) => (Some(Arc::new(current)), None),
+                    Some(Err(err)) => (None, Some(err)),
+                    None => (None, None),
+                };
+
+                let mut version_names = Vec::new();
+                self.files.remove(&previous);
+
+                for (name, files) in &mut self.files_by_query_name {
+                    if files.remove(&previous) {
+                        files.extend(current.clone());
+                        version_names.push(name.clone());
+                    }
+                }
+
+                if let Some(error) = parse_error {
+                    diagnostics.push(Diagnostic {
+                        query: version_names.iter().min().cloned().unwrap_or_default(),
+                        path: Some(path.clone()),
+                        error,
+                    });
+                }
+
+                if let Some(current) = current {
+                    self.files_by_path
+                        .insert(path.clone(), Arc::clone(&current));
+                    self.files.insert(current);
+                }
+
+                names.extend(version_names);
+            }
+
+            if let Some(error) = read_error {
+                diagnostics.push(Diagnostic {
+                    query: names.iter().min().cloned().unwrap_or_default(),
+                    path: Some(path.clone()),
+                    error,
+                });
+            }
+
+            affected.extend(names);
+        }
+
+        if !created.is_empty() {
+            let language_map = LanguageMap::from_configuration(configuration)?;
+            let mut parsed = HashMap::<(PathBuf, RecognizedLanguage), Arc<IndexedFile>>::new();
+
+            for antenna_query in &configuration.queries {
+                let mut report = |path: Option<&Path>, error| {
+                    diagnostics.push(Diagnostic {
+                        query: antenna_query.name.clone(),
+                        path: path.map(Path::to_path_buf),
+                        error,
+                    });
+                };
+
+                let (forced_language, discovered) =
+                    match Self::discover_created(antenna_query, &created) {
+                        Ok(discovered) => discovered,
+                        Err(err) => {
+                            report(None, err);
+                            continue;
+                        },
+                    };
+
+                for path in discovered {
+                    let indexed = Self::read_file(&path, &language_map, forced_language)
+                        .and_then(|(content, language)| {
+                            let key = (path.clone(), language);
+
+                            match parsed.get(&key) {
+                                Some(indexed) => Ok(Arc::clone(indexed)),
+                                None => {
+                                    let indexed =
+                                        IndexedFile::parse(&path, content, language, None)?;
+                                    let indexed = Arc::new(indexed);
+
+                                    parsed.insert(key, Arc::clone(&indexed));
+                                    Ok(indexed)
+                                },
+                            }
+                        });
+
+                    let indexed = match indexed {
+                        Ok(indexed) => indexed,
+                        Err(err) => {
+                            report(Some(&path), err);
+                            continue;
+                        },
+                    };
+
+                    if !indexed.is_queried_by(antenna_query) {
+                        continue;
+                    }
+
+                    self.files_by_query_name
+                        .entry(antenna_query.name.to_owned())
+                        .or_default()
+                        .insert(Arc::clone(&indexed));
+
+                    self.files_by_path.insert(path, Arc::clone(&indexed));
+                    self.files.insert(indexed);
+                    affected.insert(antenna_query.name.clone());
+                }
+            }
+        }
+
+        match configuration.error_policy {
+            AntennaErrorPolicy::Fail if !diagnostics.is_empty() => {
+                Err(diagnostics.swap_remove(0).into_error())
+            },
+
+            AntennaErrorPolicy::Ignore => Ok(affected),
+            _ => {
+                self.diagnostics.extend(diagnostics);
+                Ok(affected)
+            },
+        }
+    }
+
+    /// Takes the [diagnostics](Diagnostic) collected so far, leaving none behind.
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
 }
 
 impl Indexer {
     /// Maps an [`AntennaQuery`] to values for the `queries` and `files` fields in an [`Indexer`].
-    #[allow(clippy::type_complexity)]
+    ///
+    /// Discovered files without [`Changes`] are skipped, if any are given, and files whose results
+    /// are stored in the [`Cache`] are not parsed, their cached results are used instead.
+    ///
+    /// If the `error_policy` is [`AntennaErrorPolicy::Fail`] the first error encountered is
+    /// returned, otherwise errors are collected as [diagnostics](Diagnostic).
     fn map_antenna_queries(
         antenna_query: &AntennaQuery,
-    ) -> AntennaResult<(HashSet<Arc<IndexedFile>>, (String, String))> {
-        let include_paths = glob::glob(&antenna_query.include)?;
+        language_map: &LanguageMap,
+        changes: Option<&Changes>,
+        cache: Option<&Cache>,
+        error_policy: AntennaErrorPolicy,
+    ) -> AntennaResult<QueryIndex> {
+        let forced_language = antenna_query.forced_language()?;
         let mut files = HashSet::new();
-        let mut errors = Vec::new();
+        let mut cached = Vec::new();
+        let mut diagnostics = Vec::new();
+        let mut skipped = Vec::new();
+
+        let mut report = |path: Option<&Path>, error: AntennaError| {
+            let diagnostic = Diagnostic {
+                query: antenna_query.name.clone(),
+                path: path.map(Path::to_path_buf),
+                error,
+            };
+
+            match error_policy {
+                AntennaErrorPolicy::Fail => Err(diagnostic.into_error()),
+                _ => {
+                    diagnostics.push(diagnostic);
+                    Ok(())
+                },
+            }
+        };
 
-        for path in include_paths {
-            match path {
-                Ok(path) => {
-                    match Self::index_file(path) {
-                        Ok(indexed) => {
+        let mut include_paths = match discover::discover_paths(antenna_query) {
+            Ok(paths) => paths,
+            Err(AntennaError::Collection { errors }) => {
+                for error in errors {
+                    report(None, error)?;
+                }
+
+                HashSet::new()
+            },
+
+            Err(err) => {
+                report(None, err)?;
+                HashSet::new()
+            },
+        };
+
+        if let Some(changes) = changes {
+            include_paths.retain(|x| changes.contains_file(x));
+        }
+
+        for path in &include_paths {
+            let indexed = match Self::read_file(path, language_map, forced_language) {
+                Ok((content, language)) => {
+                    let entry = cache.and_then(|cache| {
+                        cache.get(&cache.key(antenna_query, path, &content, language))
+                    });
+
+                    if let Some(entry) = entry {
+                        cached.push(entry.into_out_query(&antenna_query.name));
+                        continue;
+                    }
+
+                    IndexedFile::parse(path, content, language, None)
+                },
+
+                Err(err) => Err(err),
+            };
+
+            match indexed {
+                Ok(indexed) => {
+                    let language = indexed.recognized_language;
+
+                    match indexed.is_queried_by(antenna_query) {
+                        true => {
                             files.insert(Arc::new(indexed));
                         },
 
-                        Err(err) => errors.push(err),
+                        false => {
+                            skipped.push(Diagnostic {
+                                query: antenna_query.name.clone(),
+                                path: Some(path.to_owned()),
+                                error: AntennaError::Antenna {
+                                    message: format!(
+                                        "Skipped, query has no variant for `{}`",
+                                        language.name()
+                                    ),
+                                },
+                            });
+                        },
                     }
                 },
 
-                Err(err) => errors.push(AntennaError::Glob { inner: err }),
+                Err(err) => report(Some(path), err)?,
             }
         }
 
-        if !errors.is_empty() {
-            return Err(AntennaError::Collection { errors });
+        diagnostics.extend(skipped);
+
+        Ok(QueryIndex {
+            name: antenna_query.name.clone(),
+            files,
+            cached,
+            diagnostics,
+        })
+    }
+
+    /// Determines the language forced by the given [`AntennaQuery`], if any, along with the
+    /// `created` paths it discovers.
+    fn discover_created(
+        antenna_query: &AntennaQuery,
+        created: &HashSet<PathBuf>,
+    ) -> AntennaResult<(Option<RecognizedLanguage>, Vec<PathBuf>)> {
+        let mut is_included = false;
+
+        for path in created {
+            is_included |= discover::is_included(antenna_query, path)?;
+        }
+
+        if !is_included {
+            return Ok((None, Vec::new()));
         }
 
+        let forced_language = antenna_query.forced_language()?;
+        let discovered = discover::discover_paths(antenna_query)?;
+
         Ok((
-            files,
-            (antenna_query.name.clone(), antenna_query.include.clone()),
+            forced_language,
+            discovered.intersection(created).cloned().collect(),
         ))
     }
 
-    /// Creates an [`IndexedFile`] via reading the file at the given `path`.
-    fn index_file<P>(path: P) -> AntennaResult<IndexedFile>
+    /// Reads the content of the file at the given `path`, along with the language it should be
+    /// parsed as, the `forced_language` if given, otherwise the language detected via the
+    /// `language_map`.
+    fn read_file<P>(
+        path: P,
+        language_map: &LanguageMap,
+        forced_language: Option<RecognizedLanguage>,
+    ) -> AntennaResult<(Vec<u8>, RecognizedLanguage)>
     where
         P: AsRef<Path>,
     {
         let mut file = OpenOptions::new().read(true).open(&path)?;
         let mut content = Vec::with_capacity(0xF4240);
-        let mut parser = Parser::new();
 
         file.read_to_end(&mut content)?;
 
-        let extension = path
-            .as_ref()
-            .extension()
-            .and_then(|x| x.to_os_string().into_string().ok())
-            .ok_or(AntennaError::Antenna {
-                message: format!("File `{:?}` does not have an extension", path.as_ref()),
-            })?;
-
-        let recognized_language = RecognizedLanguage::from_language_extension(&extension)
+        let recognized_language = forced_language
+            .or_else(|| language_map.detect(&path, &content))
             .ok_or(AntennaError::Antenna {
                 message: format!(
                     "File `{:?}` is not part of a `RecognizedLanguage`",
@@ -174,6 +519,100 @@ impl Indexer {
                 ),
             })?;
 
+        Ok((content, recognized_language))
+    }
+}
+
+/// The files and [diagnostics](Diagnostic) produced when indexing a single [`AntennaQuery`].
+struct QueryIndex {
+    name: String,
+    files: HashSet<Arc<IndexedFile>>,
+    cached: Vec<out::Query<'static>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Represents a file that has been indexed via an [`Indexer`].
+#[derive(Debug)]
+pub struct IndexedFile {
+    pub recognized_language: RecognizedLanguage,
+    pub extension: Option<String>,
+    pub content: Vec<u8>,
+    pub path: PathBuf,
+    pub name: String,
+    pub tree: Tree,
+    pub injections: Vec<Injection>,
+    pub suppressions: Suppressions,
+}
+
+impl IndexedFile {
+    /// Creates an [`IndexedFile`] via parsing the `content` of the file at the given `path` as the
+    /// given `recognized_language`.
+    ///
+    /// If a `previous` version of the file in the same language is given, its tree is edited to
+    /// reflect the changes between both versions and reused, so only the changed parts of the file
+    /// are re-parsed.
+    ///
+    /// Code of other languages embedded in the file is parsed as [injections](Injection), and
+    /// [suppression comments](Suppressions) are collected from every parsed tree.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use antenna::{process::index::IndexedFile, RecognizedLanguage};
+    /// use tree_sitter::Node;
+    ///
+    /// fn nodes(node: Node, output: &mut Vec<(String, std::ops::Range<usize>)>) {
+    ///     output.push((node.kind().to_owned(), node.byte_range()));
+    ///
+    ///     for child in node.children(&mut node.walk()) {
+    ///         nodes(child, output);
+    ///     }
+    /// }
+    ///
+    /// let parse = |content: &str, previous: Option<&IndexedFile>| {
+    ///     let content = content.as_bytes().to_vec();
+    ///     IndexedFile::parse("main.rs", content, RecognizedLanguage::Rust, previous).unwrap()
+    /// };
+    ///
+    /// let old = parse("fn a() {}\n\nfn b() { c(); }\n", None);
+    ///
+    /// let new_contents = [
+    ///     "fn z() {}\nfn a() {}\n\nfn b() { c(); }\n",
+    ///     "fn a() {}\n\nfn b() { c(); }\nfn z() {}\n",
+    ///     "fn a() {\n    x();\n    y();\n}\n\nfn b() { c(); }\n",
+    ///     "struct S;\n",
+    /// ];
+    ///
+    /// for new_content in new_contents {
+    ///     let (edited, fresh) = (parse(new_content, Some(&old)), parse(new_content, None));
+    ///     let (mut edited_nodes, mut fresh_nodes) = (Vec::new(), Vec::new());
+    ///
+    ///     nodes(edited.tree.root_node(), &mut edited_nodes);
+    ///     nodes(fresh.tree.root_node(), &mut fresh_nodes);
+    ///
+    ///     assert_eq!(
+    ///         edited.tree.root_node().to_sexp(),
+    ///         fresh.tree.root_node().to_sexp()
+    ///     );
+    ///     assert_eq!(edited_nodes, fresh_nodes);
+    /// }
+    /// ```
+    pub fn parse<P>(
+        path: P,
+        content: Vec<u8>,
+        recognized_language: RecognizedLanguage,
+        previous: Option<&IndexedFile>,
+    ) -> AntennaResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let mut parser = Parser::new();
+
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|x| x.to_os_string().into_string().ok());
+
         let name = path
             .as_ref()
             .file_name()
@@ -182,34 +621,96 @@ impl Indexer {
                 message: format!("Could not retrieve `{:?}`'s file name", path.as_ref()),
             })?;
 
+        let old_tree = previous
+            .filter(|x| x.recognized_language == recognized_language)
+            .map(|previous| {
+                let mut tree = previous.tree.clone();
+                tree.edit(&input_edit(&previous.content, &content));
+                tree
+            });
+
+        let content = String::from_utf8(content)?;
         parser.set_language(recognized_language.as_tree_sitter_language())?;
 
         let tree = parser
-            .parse(String::from_utf8(content.clone())?, None)
+            .parse(&content, old_tree.as_ref())
             .ok_or(AntennaError::Antenna {
                 message: format!("Failed to parse `{:?}`", path.as_ref()),
             })?;
 
-        Ok(IndexedFile {
+        let content = content.into_bytes();
+        let injections = super::inject::parse_injections(recognized_language, &tree, &content)?;
+        let suppressions = Suppressions::from_trees(
+            std::iter::once(&tree).chain(injections.iter().map(|x| &x.tree)),
+            &content,
+        );
+
+        Ok(Self {
             path: path.as_ref().to_path_buf(),
             recognized_language,
             extension,
             content,
             name,
             tree,
+            injections,
+            suppressions,
         })
     }
+
+    /// Determines whether the given [`AntennaQuery`] has a variant for the language of the file or
+    /// for the language of one of its [injections](Injection).
+    pub fn is_queried_by(&self, antenna_query: &AntennaQuery) -> bool {
+        let injected = self.injections.iter().any(|x| {
+            antenna_query
+                .query
+                .for_injected_language(x.recognized_language)
+                .is_some()
+        });
+
+        antenna_query
+            .query
+            .for_language(self.recognized_language)
+            .is_some()
+            || injected
+    }
 }
 
-/// Represents a file that has been indexed via an [`Indexer`].
-#[derive(Debug)]
-pub struct IndexedFile {
-    pub recognized_language: RecognizedLanguage,
-    pub extension: String,
-    pub content: Vec<u8>,
-    pub path: PathBuf,
-    pub name: String,
-    pub tree: Tree,
+/// Computes the [`InputEdit`] transforming the `old` content of a file into the `new` content,
+/// spanning from the first to the last byte that differs between both.
+fn input_edit(old: &[u8], new: &[u8]) -> InputEdit {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_end = old.len() - suffix;
+    let new_end = new.len() - suffix;
+
+    InputEdit {
+        start_byte: prefix,
+        old_end_byte: old_end,
+        new_end_byte: new_end,
+        start_position: point_at(old, prefix),
+        old_end_position: point_at(old, old_end),
+        new_end_position: point_at(new, new_end),
+    }
+}
+
+/// Computes the [`Point`] of the byte at the given `offset` within the `content`.
+fn point_at(content: &[u8], offset: usize) -> Point {
+    let preceding = &content[..offset];
+    let line_start = preceding
+        .iter()
+        .rposition(|&x| x == b'\n')
+        .map_or(0, |x| x + 1);
+
+    Point::new(
+        preceding.iter().filter(|&&x| x == b'\n').count(),
+        offset - line_start,
+    )
 }
 
 impl PartialEq for IndexedFile {
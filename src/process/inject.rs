@@ -0,0 +1,148 @@
+use tree_sitter::{Node, Parser, Range, Tree};
+
+use crate::{AntennaError, AntennaResult, RecognizedLanguage};
+
+/// A region of a host file containing code of another language, such as a `<script>` block in
+/// HTML or a fenced code block in Markdown, parsed with the grammar of that language.
+///
+/// The [`Tree`] is parsed over the host file's content restricted to the injected range, so the
+/// positions of its nodes are relative to the host file.
+#[derive(Debug)]
+pub struct Injection {
+    pub recognized_language: RecognizedLanguage,
+    pub range: Range,
+    pub tree: Tree,
+}
+
+/// Finds and parses every [`Injection`] within the `tree` of a host file of the given `language`.
+///
+/// Injected regions in a language without a [`RecognizedLanguage`] are skipped.
+pub fn parse_injections(
+    language: RecognizedLanguage,
+    tree: &Tree,
+    content: &[u8],
+) -> AntennaResult<Vec<Injection>> {
+    let mut parser = Parser::new();
+    let mut injections = Vec::new();
+
+    for (recognized_language, range) in find_injected_ranges(language, tree, content) {
+        parser.set_language(recognized_language.as_tree_sitter_language())?;
+        parser.set_included_ranges(&[range]).map_err(|_| {
+            AntennaError::Antenna {
+                message: format!("Invalid injected range {range:?}"),
+            }
+        })?;
+
+        let tree = parser.parse(content, None).ok_or(AntennaError::Antenna {
+            message: format!("Failed to parse injected `{}`", recognized_language.name()),
+        })?;
+
+        injections.push(Injection {
+            recognized_language,
+            range,
+            tree,
+        });
+    }
+
+    Ok(injections)
+}
+
+/// Finds the ranges of all injected code within the `tree` of a host file, in document order.
+fn find_injected_ranges(
+    language: RecognizedLanguage,
+    tree: &Tree,
+    content: &[u8],
+) -> Vec<(RecognizedLanguage, Range)> {
+    let mut ranges = Vec::new();
+    let mut cursor = tree.walk();
+    let mut stack = vec![tree.root_node()];
+
+    while let Some(node) = stack.pop() {
+        let injected = match (language.name(), node.kind()) {
+            ("html", "script_element") => {
+                script_language(node, content).zip(child_of_kind(node, "raw_text"))
+            },
+
+            ("html", "style_element") => {
+                RecognizedLanguage::from_name("css").zip(child_of_kind(node, "raw_text"))
+            },
+
+            ("markdown", "fenced_code_block") => {
+                child_of_kind(node, "info_string")
+                    .and_then(|x| child_of_kind(x, "language"))
+                    .and_then(|x| x.utf8_text(content).ok())
+                    .and_then(info_string_language)
+                    .zip(child_of_kind(node, "code_fence_content"))
+            },
+
+            _ => None,
+        };
+
+        match injected {
+            Some((injected_language, injected_node)) => {
+                ranges.push((injected_language, injected_node.range()));
+            },
+
+            None => {
+                let children = node.children(&mut cursor).collect::<Vec<_>>();
+                stack.extend(children.into_iter().rev());
+            },
+        }
+    }
+
+    ranges
+}
+
+/// Determines the language of an HTML `<script>` element from its `type` attribute.
+fn script_language(node: Node, content: &[u8]) -> Option<RecognizedLanguage> {
+    let script_type = child_of_kind(node, "start_tag").and_then(|start_tag| {
+        let mut cursor = start_tag.walk();
+        let attributes = start_tag
+            .children(&mut cursor)
+            .filter(|x| x.kind() == "attribute");
+
+        let script_type = attributes
+            .filter(|x| {
+                child_of_kind(*x, "attribute_name")
+                    .and_then(|x| x.utf8_text(content).ok())
+                    .is_some_and(|x| x.eq_ignore_ascii_case("type"))
+            })
+            .find_map(|x| {
+                let value = child_of_kind(x, "quoted_attribute_value")
+                    .and_then(|x| child_of_kind(x, "attribute_value"))
+                    .or_else(|| child_of_kind(x, "attribute_value"))?;
+
+                value.utf8_text(content).ok().map(str::to_lowercase)
+            });
+
+        script_type
+    });
+
+    match script_type.as_deref() {
+        None | Some("" | "module" | "text/javascript" | "application/javascript") => {
+            RecognizedLanguage::from_name("javascript")
+        },
+
+        Some("importmap" | "application/json" | "application/ld+json" | "speculationrules") => {
+            RecognizedLanguage::from_name("json")
+        },
+
+        Some(_) => None,
+    }
+}
+
+/// Determines the language of a Markdown fenced code block from the first word of its info
+/// string, either as a language name such as `python` or an extension such as `py`.
+fn info_string_language(info: &str) -> Option<RecognizedLanguage> {
+    let info = info.trim_start_matches('{').trim_start_matches('.');
+
+    RecognizedLanguage::from_name(info)
+        .or_else(|| RecognizedLanguage::from_language_extension(info))
+}
+
+/// Retrieves the first named child of the `node` of the given `kind`.
+fn child_of_kind<'a>(node: Node<'a>, kind: &str) -> Option<Node<'a>> {
+    let mut cursor = node.walk();
+    let child = node.named_children(&mut cursor).find(|x| x.kind() == kind);
+    child
+}
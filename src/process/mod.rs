@@ -1,84 +1,204 @@
 use std::{borrow::Cow, collections::HashMap};
 
-use tree_sitter::{Query, QueryCursor};
+use rayon::prelude::*;
+use tree_sitter::{Point, QueryCursor, Tree};
 
 use crate::{configuration::AntennaQuery, AntennaResult};
 
+pub mod discover;
 pub mod index;
-
+pub mod inject;
+pub mod predicate;
+pub mod query;
+pub mod suppress;
+
+/// Executes an [`AntennaQuery`] against every file indexed for it, in parallel.
+///
+/// If the [`Indexer`](index::Indexer) has a [`Cache`](crate::cache::Cache), the results of each
+/// executed file are stored in it, and the cached results of files skipped while indexing are
+/// included in the returned queries.
+///
+/// The resulting [queries](crate::out::Query) are ordered by the path of their file, regardless of
+/// the order in which they were executed.
 pub fn execute_antenna_query<'a>(
     antenna_query: &'a AntennaQuery,
     indexer: &'a index::Indexer,
+    query_cache: &query::QueryCache,
 ) -> AntennaResult<Vec<crate::out::Query<'a>>> {
     let files = indexer
         .get_files_by_query_name(&antenna_query.name)
-        .expect("The `Indexer` should contain indicies for the given query")
+        .into_iter()
+        .flatten()
         .collect::<Vec<_>>();
 
-    let mut out_queries = Vec::new();
+    let mut out_queries = files
+        .par_iter()
+        .map(|file| {
+            let out_query = execute_antenna_query_on_file(antenna_query, file, query_cache)?;
 
-    for file in &files {
-        let mut out_query = crate::out::Query {
-            name: Cow::Borrowed(&antenna_query.name),
-            path: Cow::Borrowed(&file.path),
-            matches: Vec::new(),
-        };
+            if let Some(cache) = indexer.cache() {
+                let key = cache.key(
+                    antenna_query,
+                    &file.path,
+                    &file.content,
+                    file.recognized_language,
+                );
 
-        let query = Query::new(
-            file.recognized_language.as_tree_sitter_language(),
-            &antenna_query.query,
-        )?;
+                cache.insert(key, crate::cache::CacheEntry::from_out_query(&out_query));
+            }
 
-        let mut query_cursor = QueryCursor::new();
+            Ok(out_query)
+        })
+        .collect::<AntennaResult<Vec<_>>>()?;
 
-        let capture_indices_to_names = query
-            .capture_names()
+    out_queries.extend(
+        indexer
+            .get_cached_by_query_name(&antenna_query.name)
             .iter()
-            .flat_map(|x| query.capture_index_for_name(x).map(|i| (i, x)))
-            .collect::<HashMap<_, _>>();
+            .cloned(),
+    );
 
-        let query_matches =
-            query_cursor.matches(&query, file.tree.root_node(), file.content.as_slice());
+    out_queries.sort_by(|a, b| a.path.cmp(&b.path));
 
-        for query_match in query_matches {
-            let mut out_match = crate::out::Match {
-                captures: Vec::new(),
-            };
+    Ok(out_queries)
+}
 
-            let filtered = query_match
-                .captures
-                .iter()
-                .filter(|x| capture_indices_to_names.contains_key(&x.index));
+/// Executes an [`AntennaQuery`] against a single [`IndexedFile`](index::IndexedFile).
+///
+/// Besides the file itself, the query is executed against every [injection](inject::Injection)
+/// in the file for which it has a language specific variant. Matches suppressed via comments in
+/// the file are kept separately from the remaining matches, see [`suppress::Suppressions`].
+pub fn execute_antenna_query_on_file<'a>(
+    antenna_query: &'a AntennaQuery,
+    file: &'a index::IndexedFile,
+    query_cache: &query::QueryCache,
+) -> AntennaResult<crate::out::Query<'a>> {
+    let mut out_query = crate::out::Query {
+        name: Cow::Borrowed(&antenna_query.name),
+        path: Cow::Borrowed(&file.path),
+        matches: Vec::new(),
+        suppressed: Vec::new(),
+    };
+
+    let injected = file
+        .injections
+        .iter()
+        .filter(|x| {
+            antenna_query
+                .query
+                .for_injected_language(x.recognized_language)
+                .is_some()
+        })
+        .map(|x| (x.recognized_language, &x.tree));
+
+    for (language, tree) in
+        std::iter::once((file.recognized_language, &file.tree)).chain(injected)
+    {
+        let Some(compiled) = query_cache.get_or_compile(antenna_query, language)? else {
+            continue;
+        };
 
-            let file_bytes = file.content.as_slice();
+        let matches = execute_query_on_tree(antenna_query, &compiled, tree, file)?;
 
-            for query_capture in filtered {
-                let range = query_capture.node.range();
+        for out_match in matches {
+            match file
+                .suppressions
+                .suppresses(&antenna_query.name, &out_match)
+            {
+                true => out_query.suppressed.push(out_match),
+                false => out_query.matches.push(out_match),
+            }
+        }
+    }
 
-                let out_capture = crate::out::Capture {
-                    name: capture_indices_to_names
-                        .get(&query_capture.index)
-                        .map(|&x| x.clone())
-                        .unwrap_or_default(),
+    Ok(out_query)
+}
 
-                    text: String::from_utf8(
-                        file_bytes[range.start_byte..range.end_byte].to_vec(),
-                    )?,
+/// Executes a [`CompiledQuery`](query::CompiledQuery) against a [`Tree`] parsed from the content
+/// of the given [`IndexedFile`](index::IndexedFile), dropping matches which do not satisfy its
+/// [predicates](predicate::Predicates).
+fn execute_query_on_tree(
+    antenna_query: &AntennaQuery,
+    compiled: &query::CompiledQuery,
+    tree: &Tree,
+    file: &index::IndexedFile,
+) -> AntennaResult<Vec<crate::out::Match>> {
+    let query = &compiled.query;
+    let content = file.content.as_slice();
+    let mut out_matches = Vec::new();
+    let mut query_cursor = QueryCursor::new();
+
+    let capture_indices_to_names = query
+        .capture_names()
+        .iter()
+        .flat_map(|x| query.capture_index_for_name(x).map(|i| (i, x)))
+        .collect::<HashMap<_, _>>();
+
+    let query_matches = query_cursor.matches(query, tree.root_node(), content);
+
+    for query_match in query_matches {
+        if !compiled
+            .predicates
+            .satisfied_by(&query_match, content, &file.path)
+        {
+            continue;
+        }
 
-                    start_column: range.start_point.column,
-                    start_line: range.start_point.row,
-                    end_column: range.end_point.column,
-                    end_line: range.end_point.row,
-                };
+        let mut out_match = crate::out::Match {
+            captures: Vec::new(),
+            message: None,
+            fingerprint: String::new(),
+        };
 
-                out_match.captures.push(out_capture);
-            }
+        let filtered = query_match
+            .captures
+            .iter()
+            .filter(|x| capture_indices_to_names.contains_key(&x.index))
+            .collect::<Vec<_>>();
+
+        for query_capture in &filtered {
+            let range = query_capture.node.range();
+
+            let out_capture = crate::out::Capture {
+                name: capture_indices_to_names
+                    .get(&query_capture.index)
+                    .map(|&x| x.clone())
+                    .unwrap_or_default(),
+
+                text: String::from_utf8(content[range.start_byte..range.end_byte].to_vec())?,
+
+                start_column: range.start_point.column,
+                start_line: range.start_point.row,
+                end_column: range.end_point.column,
+                end_line: range.end_point.row,
+                start_utf16_column: utf16_column(content, range.start_byte, range.start_point),
+                end_utf16_column: utf16_column(content, range.end_byte, range.end_point),
+            };
+
+            out_match.captures.push(out_capture);
+        }
+
+        out_match.fingerprint = crate::baseline::fingerprint(
+            filtered
+                .iter()
+                .map(|x| (capture_indices_to_names[&x.index].as_str(), x.node)),
+            content,
+        );
 
-            out_query.matches.push(out_match);
+        if let Some(template) = &antenna_query.message {
+            out_match.message = Some(out_match.render_message(template));
         }
 
-        out_queries.push(out_query);
+        out_matches.push(out_match);
     }
 
-    Ok(out_queries)
+    Ok(out_matches)
+}
+
+/// Converts the byte column of a `point` at the given `byte` offset of the `content` into a
+/// column in UTF-16 code units, bytes which are not valid UTF-8 count as a single code unit.
+fn utf16_column(content: &[u8], byte: usize, point: Point) -> usize {
+    String::from_utf8_lossy(&content[byte - point.column..byte])
+        .encode_utf16()
+        .count()
 }
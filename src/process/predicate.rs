@@ -0,0 +1,416 @@
+use std::{borrow::Cow, path::Path};
+
+use glob::Pattern;
+use tree_sitter::{Node, Query, QueryError, QueryErrorKind, QueryMatch, QueryPredicateArg};
+
+use super::discover;
+
+/// A predicate evaluated by antenna rather than Tree Sitter, each of which may be negated via a
+/// `not-` prefix.
+///
+/// The `#eq?`, `#not-eq?`, `#match?` and `#not-match?` predicates are evaluated by Tree Sitter
+/// itself while matching.
+#[derive(Debug)]
+pub enum Predicate {
+    /// `(#any-of? @capture "a" "b" ...)`, the text of the capture is one of the given strings.
+    AnyOf {
+        capture: u32,
+        values: Vec<String>,
+        positive: bool,
+    },
+
+    /// `(#path-match? "pattern")`, the path of the file matches the given glob pattern.
+    PathMatch { pattern: Pattern, positive: bool },
+
+    /// `(#line-count>? @capture count)`, the capture spans more than the given number of lines.
+    ///
+    /// As Tree Sitter does not allow `>` in predicate names, this is rewritten to
+    /// `#line-count-gt?` via [`normalize_query_text`] prior to compiling a query, and errors
+    /// are mapped back via [`denormalize_error`].
+    LineCountGreater {
+        capture: u32,
+        count: usize,
+        positive: bool,
+    },
+
+    /// `(#has-ancestor? @capture kind ...)`, the capture has an ancestor of one of the given kinds.
+    HasAncestor {
+        capture: u32,
+        kinds: Vec<String>,
+        positive: bool,
+    },
+}
+
+/// Name of the `#line-count>?` predicate, which Tree Sitter is unable to parse.
+const LINE_COUNT_GREATER: &str = "line-count>?";
+
+/// Name the `#line-count>?` predicate is rewritten to prior to compiling a query.
+const LINE_COUNT_GREATER_NORMALIZED: &str = "line-count-gt?";
+
+/// Rewrites the names of [predicates](Predicate) Tree Sitter is unable to parse to equivalent
+/// names it is able to parse. Only predicate names following a `#` are rewritten, string
+/// literals and comments are left as they are.
+///
+/// ```
+/// # use antenna::process::predicate::normalize_query_text;
+/// let text = r##"((block) @b (#line-count>? @b 50) (#not-eq? @b "#line-count>?"))"##;
+/// assert_eq!(
+///     normalize_query_text(text),
+///     r##"((block) @b (#line-count-gt? @b 50) (#not-eq? @b "#line-count>?"))"##
+/// );
+/// ```
+pub fn normalize_query_text(text: &str) -> Cow<'_, str> {
+    let offsets = rewritten_offsets(text);
+
+    match offsets.is_empty() {
+        true => Cow::Borrowed(text),
+        false => {
+            let mut normalized = String::with_capacity(text.len() + 2 * offsets.len());
+            let mut position = 0;
+
+            for offset in offsets {
+                normalized.push_str(&text[position..offset]);
+                normalized.push_str(LINE_COUNT_GREATER_NORMALIZED);
+                position = offset + LINE_COUNT_GREATER.len();
+            }
+
+            normalized.push_str(&text[position..]);
+            Cow::Owned(normalized)
+        },
+    }
+}
+
+/// Maps a [`QueryError`] raised while compiling the text [`normalize_query_text`] produced for
+/// the given `text` back to the corresponding position within `text`, quoting the line of `text`
+/// in the message of syntax errors.
+pub fn denormalize_error(text: &str, err: QueryError) -> QueryError {
+    let growth = LINE_COUNT_GREATER_NORMALIZED.len() - LINE_COUNT_GREATER.len();
+    let mut shift = 0;
+    let mut offset = None;
+
+    for original in rewritten_offsets(text) {
+        let start = original + shift;
+
+        if err.offset < start {
+            break;
+        }
+
+        if err.offset < start + LINE_COUNT_GREATER_NORMALIZED.len() {
+            offset = Some(original);
+            break;
+        }
+
+        shift += growth;
+    }
+
+    let offset = offset.unwrap_or(err.offset - shift).min(text.len());
+    let (row, column) = point_at(text, offset);
+
+    let message = match err.kind == QueryErrorKind::Syntax && err.message.contains('\n') {
+        true => {
+            let line = text[offset - column..].lines().next().unwrap_or_default();
+            format!("{line}\n{}^", " ".repeat(column))
+        },
+
+        false => err.message,
+    };
+
+    QueryError {
+        row,
+        column,
+        offset,
+        message,
+        kind: err.kind,
+    }
+}
+
+/// Finds the offsets of predicate names within the given query `text` that are rewritten by
+/// [`normalize_query_text`], skipping string literals and comments.
+fn rewritten_offsets(text: &str) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let mut characters = text.char_indices();
+
+    while let Some((i, character)) = characters.next() {
+        match character {
+            '"' => {
+                let mut escaped = false;
+
+                for (_, x) in characters.by_ref() {
+                    match (escaped, x) {
+                        (false, '"') => break,
+                        (false, '\\') => escaped = true,
+                        _ => escaped = false,
+                    }
+                }
+            },
+
+            ';' => {
+                characters.by_ref().find(|(_, x)| *x == '\n');
+            },
+
+            '#' => {
+                let name = &text[i + 1..];
+                let name_offset = i + 1 + name.strip_prefix("not-").map_or(0, |_| 4);
+
+                if text[name_offset..].starts_with(LINE_COUNT_GREATER) {
+                    offsets.push(name_offset);
+                }
+            },
+
+            _ => {},
+        }
+    }
+
+    offsets
+}
+
+/// Computes the zero-based row and byte column of the given `offset` within the `text`.
+fn point_at(text: &str, offset: usize) -> (usize, usize) {
+    let preceding = &text[..offset];
+
+    (
+        preceding.matches('\n').count(),
+        offset - preceding.rfind('\n').map_or(0, |x| x + 1),
+    )
+}
+
+/// The [predicates](Predicate) of every pattern in a [`Query`], indexed by pattern.
+#[derive(Debug, Default)]
+pub struct Predicates {
+    patterns: Vec<Vec<Predicate>>,
+}
+
+impl Predicates {
+    /// Parses the [predicates](Predicate) of every pattern in the given [`Query`], compiled from
+    /// the given `text`.
+    ///
+    /// If a pattern uses an unknown predicate, or a predicate with invalid arguments, this function
+    /// will return a [`QueryError`] positioned at the start of the pattern.
+    pub fn from_query(query: &Query, text: &str) -> Result<Self, QueryError> {
+        let mut patterns = Vec::with_capacity(query.pattern_count());
+
+        for pattern_index in 0..query.pattern_count() {
+            let predicates = query
+                .general_predicates(pattern_index)
+                .iter()
+                .map(|x| Predicate::parse(&x.operator, &x.args))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|message| {
+                    let offset = query.start_byte_for_pattern(pattern_index);
+                    let (row, column) = point_at(text, offset);
+
+                    QueryError {
+                        row,
+                        column,
+                        offset,
+                        message,
+                        kind: QueryErrorKind::Predicate,
+                    }
+                })?;
+
+            patterns.push(predicates);
+        }
+
+        Ok(Self { patterns })
+    }
+
+    /// Determines whether the given [`QueryMatch`], found in a file at the given `path` with the
+    /// given `content`, satisfies every [`Predicate`] of its pattern.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    ///
+    /// use antenna::{
+    ///     process::predicate::{normalize_query_text, Predicates},
+    ///     RecognizedLanguage,
+    /// };
+    /// use tree_sitter::{Parser, Query, QueryCursor};
+    ///
+    /// let language = RecognizedLanguage::Rust.as_tree_sitter_language();
+    /// let content = "fn a() {\n    x.unwrap();\n}\n\nfn b() { y.expect(\"\"); }\n";
+    ///
+    /// let mut parser = Parser::new();
+    /// parser.set_language(language).unwrap();
+    /// let tree = parser.parse(content, None).unwrap();
+    ///
+    /// let count = |text: &str, path: &str| {
+    ///     let text = normalize_query_text(text);
+    ///     let query = Query::new(language, &text).unwrap();
+    ///     let predicates = Predicates::from_query(&query, &text).unwrap();
+    ///
+    ///     QueryCursor::new()
+    ///         .matches(&query, tree.root_node(), content.as_bytes())
+    ///         .filter(|x| predicates.satisfied_by(x, content.as_bytes(), Path::new(path)))
+    ///         .count()
+    /// };
+    ///
+    /// let any_of = r#"((field_identifier) @f (#any-of? @f "unwrap" "expect"))"#;
+    /// let not_any_of = r#"((field_identifier) @f (#not-any-of? @f "unwrap"))"#;
+    /// let path_match = r#"((function_item) @f (#path-match? "src/**/*.rs"))"#;
+    /// let line_count = "((function_item) @f (#line-count>? @f 1))";
+    /// let has_ancestor = "((identifier) @i (#has-ancestor? @i block))";
+    ///
+    /// assert_eq!(count(any_of, "src/main.rs"), 2);
+    /// assert_eq!(count(not_any_of, "src/main.rs"), 1);
+    /// assert_eq!(count(path_match, "src/main.rs"), 2);
+    /// assert_eq!(count(path_match, "tests/main.rs"), 0);
+    /// assert_eq!(count(line_count, "src/main.rs"), 1);
+    /// assert_eq!(count(has_ancestor, "src/main.rs"), 2);
+    /// ```
+    pub fn satisfied_by(&self, query_match: &QueryMatch, content: &[u8], path: &Path) -> bool {
+        self.patterns
+            .get(query_match.pattern_index)
+            .into_iter()
+            .flatten()
+            .all(|x| x.satisfied_by(query_match, content, path))
+    }
+}
+
+impl Predicate {
+    /// Parses a [`Predicate`] from the `operator` and `args` of a general
+    /// [`QueryPredicate`](tree_sitter::QueryPredicate).
+    ///
+    /// If the `operator` is unknown or the `args` are invalid, this function will return a
+    /// message describing the issue.
+    fn parse(operator: &str, args: &[QueryPredicateArg]) -> Result<Self, String> {
+        let (name, positive) = match operator.strip_prefix("not-") {
+            Some(name) => (name, false),
+            None => (operator, true),
+        };
+
+        let capture = || {
+            match args.first() {
+                Some(QueryPredicateArg::Capture(capture)) => Ok(*capture),
+                _ => Err(format!("First argument to #{operator} must be a capture")),
+            }
+        };
+
+        let strings = |args: &[QueryPredicateArg]| {
+            args.iter()
+                .map(|x| {
+                    match x {
+                        QueryPredicateArg::String(value) => Ok(value.to_string()),
+                        QueryPredicateArg::Capture(_) => {
+                            Err(format!("Arguments to #{operator} must be literals"))
+                        },
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        match name {
+            "any-of?" => {
+                let capture = capture()?;
+
+                Ok(Self::AnyOf {
+                    capture,
+                    values: strings(&args[1..])?,
+                    positive,
+                })
+            },
+
+            "path-match?" => {
+                match strings(args)?.as_slice() {
+                    [pattern] => {
+                        Ok(Self::PathMatch {
+                            pattern: Pattern::new(discover::normalize_pattern(pattern))
+                                .map_err(|err| format!("Invalid pattern `{pattern}`: {err}"))?,
+                            positive,
+                        })
+                    },
+
+                    _ => Err(format!("#{operator} expects a single pattern")),
+                }
+            },
+
+            "line-count-gt?" => {
+                let capture = capture()?;
+
+                match strings(&args[1..])?.as_slice() {
+                    [count] => {
+                        Ok(Self::LineCountGreater {
+                            capture,
+                            count: count
+                                .parse()
+                                .map_err(|_| format!("Invalid line count `{count}`"))?,
+                            positive,
+                        })
+                    },
+
+                    _ => Err(format!("#{operator} expects a capture and a line count")),
+                }
+            },
+
+            "has-ancestor?" => {
+                let capture = capture()?;
+                let kinds = strings(&args[1..])?;
+
+                match kinds.is_empty() {
+                    true => Err(format!("#{operator} expects at least one node kind")),
+                    false => {
+                        Ok(Self::HasAncestor {
+                            capture,
+                            kinds,
+                            positive,
+                        })
+                    },
+                }
+            },
+
+            _ => Err(format!("Unknown predicate #{operator}")),
+        }
+    }
+
+    /// Determines whether the given [`QueryMatch`] satisfies the [`Predicate`].
+    ///
+    /// Predicates on a capture are satisfied if every node of the capture satisfies them, including
+    /// when an optional capture has no nodes.
+    fn satisfied_by(&self, query_match: &QueryMatch, content: &[u8], path: &Path) -> bool {
+        let all_nodes = |capture: u32, predicate: &dyn Fn(Node) -> bool| {
+            query_match.nodes_for_capture_index(capture).all(predicate)
+        };
+
+        match self {
+            Self::AnyOf {
+                capture,
+                values,
+                positive,
+            } => {
+                all_nodes(*capture, &|node| {
+                    let text = &content[node.byte_range()];
+                    values.iter().any(|x| x.as_bytes() == text) == *positive
+                })
+            },
+
+            Self::PathMatch { pattern, positive } => {
+                pattern.matches_path_with(path, discover::MATCH_OPTIONS) == *positive
+            },
+
+            Self::LineCountGreater {
+                capture,
+                count,
+                positive,
+            } => {
+                all_nodes(*capture, &|node| {
+                    let lines = node.end_position().row - node.start_position().row + 1;
+                    (lines > *count) == *positive
+                })
+            },
+
+            Self::HasAncestor {
+                capture,
+                kinds,
+                positive,
+            } => {
+                all_nodes(*capture, &|node| {
+                    let has_ancestor = std::iter::successors(node.parent(), Node::parent)
+                        .any(|x| kinds.iter().any(|kind| kind == x.kind()));
+
+                    has_ancestor == *positive
+                })
+            },
+        }
+    }
+}
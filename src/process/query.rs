@@ -0,0 +1,161 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use tree_sitter::Query;
+
+use super::predicate::{self, Predicates};
+use crate::{
+    configuration::{AntennaConfiguration, AntennaQuery, AntennaQueryText},
+    detect::LanguageMap,
+    AntennaError, AntennaResult, RecognizedLanguage,
+};
+
+/// A cache of [compiled queries](CompiledQuery), keyed by the [`RecognizedLanguage`] the query
+/// was compiled for and the text of the query.
+#[derive(Debug, Default)]
+pub struct QueryCache {
+    queries: RwLock<HashMap<(RecognizedLanguage, String), Arc<CompiledQuery>>>,
+}
+
+/// A Tree Sitter [`Query`] along with the [predicates](Predicates) antenna evaluates for it.
+#[derive(Debug)]
+pub struct CompiledQuery {
+    pub query: Query,
+    pub predicates: Predicates,
+}
+
+impl QueryCache {
+    /// Constructs a [`QueryCache`], compiling every [`AntennaQuery`] in the given
+    /// [`configuration`](AntennaConfiguration) for the languages its `include` patterns are
+    /// restricted to, ensuring invalid queries are reported before any files are indexed.
+    pub fn from_configuration(configuration: &AntennaConfiguration) -> AntennaResult<Self> {
+        let query_cache = Self::default();
+        let language_map = LanguageMap::from_configuration(configuration)?;
+        let mut errors = Vec::new();
+
+        for antenna_query in &configuration.queries {
+            let forced_language = match antenna_query.forced_language() {
+                Ok(language) => language,
+                Err(err) => {
+                    errors.push(err);
+                    continue;
+                },
+            };
+
+            let languages = match &antenna_query.query {
+                AntennaQueryText::Shared(_) if forced_language.is_some() => {
+                    HashSet::from_iter(forced_language)
+                },
+
+                AntennaQueryText::Shared(_) => {
+                    Self::include_languages(antenna_query, &language_map)
+                },
+                AntennaQueryText::PerLanguage(variants) => {
+                    let mut languages = HashSet::new();
+
+                    for name in variants.keys() {
+                        match RecognizedLanguage::from_name(name) {
+                            Some(language) => {
+                                languages.insert(language);
+                            },
+
+                            None => {
+                                errors.push(AntennaError::Antenna {
+                                    message: format!(
+                                        "Query `{}` has a variant for the unrecognized language \
+                                         `{name}`",
+                                        antenna_query.name
+                                    ),
+                                });
+                            },
+                        }
+                    }
+
+                    languages
+                },
+            };
+
+            for language in languages {
+                if let Err(err) = query_cache.get_or_compile(antenna_query, language) {
+                    errors.push(err);
+                }
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(query_cache),
+            1 => Err(errors.remove(0)),
+            _ => Err(AntennaError::Collection { errors }),
+        }
+    }
+
+    /// Retrieves the [`CompiledQuery`] compiled from the given [`AntennaQuery`] for the given
+    /// `language`, compiling and caching it if it has not been compiled yet.
+    ///
+    /// Returns [`None`] if the [`AntennaQuery`] has no variant for the given `language`.
+    pub fn get_or_compile(
+        &self,
+        antenna_query: &AntennaQuery,
+        language: RecognizedLanguage,
+    ) -> AntennaResult<Option<Arc<CompiledQuery>>> {
+        let Some(text) = antenna_query.query.for_language(language) else {
+            return Ok(None);
+        };
+
+        let key = (language, text.to_owned());
+
+        if let Some(compiled) = self.queries.read().unwrap().get(&key) {
+            return Ok(Some(Arc::clone(compiled)));
+        }
+
+        let source = predicate::normalize_query_text(text);
+        let compiled = Query::new(language.as_tree_sitter_language(), &source)
+            .and_then(|query| {
+                let predicates = Predicates::from_query(&query, &source)?;
+                Ok(CompiledQuery { query, predicates })
+            })
+            .map_err(|err| {
+                let err = predicate::denormalize_error(text, err);
+
+                AntennaError::QueryCompile {
+                    name: antenna_query.name.clone(),
+                    language,
+                    row: err.row,
+                    column: err.column,
+                    inner: err,
+                }
+            })?;
+
+        let mut queries = self.queries.write().unwrap();
+        Ok(Some(Arc::clone(
+            queries.entry(key).or_insert(Arc::new(compiled)),
+        )))
+    }
+
+    /// Determines the languages an [`AntennaQuery`] is restricted to via the file names or
+    /// extensions of its `include` patterns, resolved via the mappings of the given
+    /// [`LanguageMap`] before the built-in file names and extensions. Patterns without either
+    /// being recognized are ignored.
+    pub fn include_languages(
+        antenna_query: &AntennaQuery,
+        language_map: &LanguageMap,
+    ) -> HashSet<RecognizedLanguage> {
+        antenna_query
+            .include
+            .iter()
+            .filter_map(|x| {
+                let path = Path::new(x);
+
+                language_map
+                    .resolve(path)
+                    .or_else(|| RecognizedLanguage::from_file_name(path.file_name()?.to_str()?))
+                    .or_else(|| {
+                        RecognizedLanguage::from_language_extension(path.extension()?.to_str()?)
+                    })
+            })
+            .collect()
+    }
+}
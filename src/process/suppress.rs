@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use tree_sitter::Tree;
+
+use crate::out::Match;
+
+/// Marker of a comment suppressing matches on the line it follows code on, or on the following
+/// line if it is on its own line.
+const IGNORE: &str = "antenna-ignore";
+
+/// Marker of a comment suppressing matches anywhere in its file.
+const IGNORE_FILE: &str = "antenna-ignore-file";
+
+/// Tokens closing a comment, which end the list of query names of a directive.
+const COMMENT_CLOSERS: [&str; 4] = ["*/", "-->", "-}", "]]"];
+
+/// The queries a suppression comment applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Scope {
+    /// Every query, as suppressed via a comment without any query names.
+    All,
+
+    /// The queries of the given names.
+    Queries(Vec<String>),
+}
+
+impl Scope {
+    /// Determines whether the [`Scope`] includes the query of the given `name`.
+    pub fn includes(&self, name: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Queries(names) => names.iter().any(|x| x == name),
+        }
+    }
+}
+
+/// A suppression parsed from the text of a comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Directive {
+    /// Whether the directive applies to the entire file, rather than to neighbouring lines.
+    pub file: bool,
+    pub scope: Scope,
+}
+
+impl Directive {
+    /// Parses a [`Directive`] from the `text` of a comment, either `antenna-ignore` or
+    /// `antenna-ignore-file` optionally followed by a colon and a comma separated list of query
+    /// names. The marker must be followed by whitespace, a colon or the end of the comment, so
+    /// prose such as `antenna-ignored` is not a directive.
+    ///
+    /// If the comment does not contain a directive this function will return [`None`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use antenna::process::suppress::{Directive, Scope};
+    ///
+    /// assert_eq!(
+    ///     Directive::parse("// antenna-ignore: no-unwrap, no-expect"),
+    ///     Some(Directive {
+    ///         file: false,
+    ///         scope: Scope::Queries(vec![String::from("no-unwrap"), String::from("no-expect")]),
+    ///     })
+    /// );
+    ///
+    /// assert_eq!(
+    ///     Directive::parse("/* antenna-ignore-file */"),
+    ///     Some(Directive {
+    ///         file: true,
+    ///         scope: Scope::All,
+    ///     })
+    /// );
+    ///
+    /// assert_eq!(
+    ///     Directive::parse("// this mentions antenna-ignored stuff"),
+    ///     None
+    /// );
+    /// assert_eq!(Directive::parse("# see antenna-ignore-files"), None);
+    /// ```
+    pub fn parse(text: &str) -> Option<Self> {
+        let (file, remaining) = text.match_indices(IGNORE).find_map(|(start, _)| {
+            let remaining = &text[start..];
+
+            let (file, remaining) = match remaining.strip_prefix(IGNORE_FILE) {
+                Some(remaining) => (true, remaining),
+                None => (false, &remaining[IGNORE.len()..]),
+            };
+
+            let is_terminated = remaining.is_empty()
+                || remaining.starts_with(|x: char| x.is_whitespace() || x == ':')
+                || COMMENT_CLOSERS.iter().any(|x| remaining.starts_with(x));
+
+            is_terminated.then_some((file, remaining))
+        })?;
+
+        let remaining = remaining.lines().next().unwrap_or_default();
+        let remaining = COMMENT_CLOSERS
+            .iter()
+            .filter_map(|x| remaining.find(x))
+            .min()
+            .map_or(remaining, |end| &remaining[..end]);
+
+        let names = remaining
+            .trim_start()
+            .strip_prefix(':')
+            .map(|names| {
+                names
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|x| !x.is_empty())
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let scope = match names.is_empty() {
+            true => Scope::All,
+            false => Scope::Queries(names),
+        };
+
+        Some(Self { file, scope })
+    }
+}
+
+/// The suppression comments found in a file, see [`Directive`].
+#[derive(Debug, Default)]
+pub struct Suppressions {
+    file: Vec<Scope>,
+    lines: HashMap<usize, Vec<Scope>>,
+}
+
+impl Suppressions {
+    /// Collects the suppression comments in all of the given `trees`, parsed from the given
+    /// `content`.
+    ///
+    /// A comment on its own line suppresses matches starting on the following line, while a
+    /// comment following code suppresses matches starting on its own line, unless either
+    /// suppresses matches in the entire file.
+    pub fn from_trees<'a, I>(trees: I, content: &[u8]) -> Self
+    where
+        I: IntoIterator<Item = &'a Tree>,
+    {
+        let mut suppressions = Self::default();
+
+        for tree in trees {
+            let mut cursor = tree.walk();
+            let mut stack = vec![tree.root_node()];
+
+            while let Some(node) = stack.pop() {
+                if !node.kind().contains("comment") {
+                    let children = node.children(&mut cursor).collect::<Vec<_>>();
+                    stack.extend(children);
+                    continue;
+                }
+
+                let Some(directive) = node.utf8_text(content).ok().and_then(Directive::parse)
+                else {
+                    continue;
+                };
+
+                match directive.file {
+                    true => suppressions.file.push(directive.scope),
+                    false => {
+                        let preceding = &content[..node.start_byte()];
+                        let line_start = preceding.iter().rposition(|&x| x == b'\n');
+                        let standalone = preceding[line_start.map_or(0, |x| x + 1)..]
+                            .iter()
+                            .all(u8::is_ascii_whitespace);
+
+                        let line = match standalone {
+                            true => node.end_position().row + 1,
+                            false => node.start_position().row,
+                        };
+
+                        suppressions
+                            .lines
+                            .entry(line)
+                            .or_default()
+                            .push(directive.scope);
+                    },
+                }
+            }
+        }
+
+        suppressions
+    }
+
+    /// Determines whether the given [`Match`] of the query of the given `name` is suppressed,
+    /// either by a comment for the entire file or one neighbouring the line the match starts on.
+    pub fn suppresses(&self, name: &str, out_match: &Match) -> bool {
+        if self.file.iter().any(|x| x.includes(name)) {
+            return true;
+        }
+
+        let Some(line) = out_match.captures.iter().map(|x| x.start_line).min() else {
+            return false;
+        };
+
+        self.lines
+            .get(&line)
+            .is_some_and(|x| x.iter().any(|x| x.includes(name)))
+    }
+}